@@ -0,0 +1,114 @@
+use crate::manifest::Manifest;
+use ice_cream_maker::catalog::FlavorCatalog;
+use std::fmt;
+
+/// Configuration of the agent caller lambda function, read once on cold start.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    /// Whether the agent sends traces, "ENABLE_TRACE" is `true` when it isn't set.
+    pub enable_trace: bool,
+    /// Whether the answer is streamed to the caller as server-sent events, "STREAM_RESPONSE" is
+    /// `false` when it isn't set.
+    pub stream_response: bool,
+    pub manifest: Manifest,
+    /// Set when the manifest has action groups that return control to the agent caller.
+    pub shop: Option<ShopConfig>,
+}
+
+/// What the agent caller needs to run the action groups of the shop itself.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShopConfig {
+    pub table_name: String,
+    pub flavor_catalog: FlavorCatalog,
+}
+
+impl Config {
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Reads the configuration from the given variables.
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self, ConfigError> {
+        let mut errors = Vec::new();
+
+        let enable_trace = flag(&var, "ENABLE_TRACE", true, &mut errors);
+        let stream_response = flag(&var, "STREAM_RESPONSE", false, &mut errors);
+
+        let manifest = match Manifest::from_vars(&var) {
+            Ok(manifest) => Some(manifest),
+            Err(error) => {
+                errors.push(format!("{error}."));
+                None
+            }
+        };
+
+        let mut shop = None;
+        if manifest.as_ref().is_some_and(Manifest::returns_control) {
+            match (
+                waiter::config::Config::from_vars(&var),
+                ice_cream_maker::config::Config::from_vars(&var),
+            ) {
+                (Ok(waiter), Ok(ice_cream_maker)) => {
+                    shop = Some(ShopConfig {
+                        table_name: waiter.table_name,
+                        flavor_catalog: ice_cream_maker.flavor_catalog,
+                    });
+                }
+                (waiter, ice_cream_maker) => {
+                    errors.extend(waiter.err().map(|error| error.0).unwrap_or_default());
+                    errors.extend(
+                        ice_cream_maker
+                            .err()
+                            .map(|error| error.0)
+                            .unwrap_or_default(),
+                    );
+                }
+            }
+        }
+
+        match (enable_trace, stream_response, manifest) {
+            (Some(enable_trace), Some(stream_response), Some(manifest)) if errors.is_empty() => {
+                Ok(Config {
+                    enable_trace,
+                    stream_response,
+                    manifest,
+                    shop,
+                })
+            }
+            _ => Err(ConfigError(errors)),
+        }
+    }
+}
+
+/// Value of a `true` or `false` variable, `default` when it isn't set.
+fn flag(
+    var: &impl Fn(&str) -> Option<String>,
+    name: &str,
+    default: bool,
+    errors: &mut Vec<String>,
+) -> Option<bool> {
+    match var(name) {
+        None => Some(default),
+        Some(value) => match value.parse::<bool>() {
+            Ok(flag) => Some(flag),
+            Err(_) => {
+                errors.push(format!(
+                    r#"Invalid "{name}" environment variable {value:?}, expected "true" or "false"."#
+                ));
+                None
+            }
+        },
+    }
+}
+
+/// Every problem found in the configuration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigError(pub Vec<String>);
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid configuration: {}", self.0.join(" "))
+    }
+}
+
+impl std::error::Error for ConfigError {}
@@ -0,0 +1,7 @@
+pub mod config;
+pub mod manifest;
+pub mod reply;
+pub mod shop;
+pub mod stream;
+pub mod trace;
+pub mod types;
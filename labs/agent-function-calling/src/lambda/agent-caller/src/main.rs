@@ -1,167 +1,220 @@
-mod types;
-
-use aws_sdk_bedrockagentruntime::types::{
-    ActionGroupExecutor, AgentActionGroup, ApiSchema, FunctionDefinition, FunctionSchema, InlineAgentResponseStream, OrchestrationTrace, ParameterDetail, ParameterType, Rationale, Trace
+use agent_caller::{
+    config::Config,
+    reply::AgentReply,
+    shop::{Session, Shop},
+    stream::{EventStream, StreamEvent},
+    trace::TraceEvent,
+    types::ClientPrompt,
 };
+use aws_sdk_bedrockagentruntime::types::{InlineAgentResponseStream, InlineSessionState};
 use lambda_runtime::{
     run, service_fn,
-    tracing::{self, instrument},
+    streaming::{Body, Response},
+    tracing::{self, instrument, Instrument},
     Error, LambdaEvent,
 };
-use types::ClientPrompt;
-
-/// The instructions that tell the inline agent what it should do and how it should interact with users.
-const AGENT_INSTRUCTION: &str = r#"
-    You are an ice cream making assistant in charge of operating the requests for an ice cream shop.
-    Recommended flow of instructions when a client request comes in is:
-    1. Identify client's name from the request
-    2. Create a new order with client's name
-    3. Identify flavors to add from the request
-    4. If any flavor needs to be added, then add those flavors to the client's order
-    5. Identify flavors to remove from the request
-    6. If any flavor needs to be removed, then delete those flavors from the client's order
-    7. If a unavailable flavor is requested, explain it is not available and omit it
-    8. Respond appropriately to requests, including:
-        - Confirming when an ice cream has been prepared
-        - Explaining if a requested flavor isn't available
-        - Rejecting the request if the flavor request is ambiguous
-        - Handling one flavor request at a time
-    9. Prepare each ice cream flavor from the order
-
-    Extra Guidelines:
-    - You can prepare these flavors:
-        1. Vanilla
-        2. Chocolate
-        3. Strawberry
-        4. Mint Chocolate Chip
-        5. Cookie Dough
-    - You can take up to 5 flavors on a given order, if there are more the order should be split.
-    - When someone requests a flavor not in this list, explain which flavors are available instead.
-    - You cannot prepare ice cream flavors if you haven't added those flavors to the given order first.
-
-    Tone: Always maintain a friendly, helpful tone while focusing on the core task of ice cream preparation.
-
-    <example>
-        <client>Hi, my name is Pedro, can I take a Vanilla ice cream, two of Chocolate and last one, hmm, make it Cookie Dough</client>
-        <rationale>
-            1. Client's name is Pedro
-            2. Flavors to add to order are:
-                - Vanilla
-                - Chocolate
-                - Chocolate
-                - Cookie Dough
-            3. Prepare Vanilla ice cream
-            4. Prepare Chocolate ice cream
-            5. Prepare Chocolate ice cream
-            6. Prepare Cookie Dough ice cream
-            7. Give ice creams to client with a friendly tone
-        </rationale>
-    </example>
-"#;
-
-#[instrument(name = "agent_caller_handler", skip(event, bedrock_agentruntime_client), fields(req_id = %event.context.request_id))]
+use std::sync::Arc;
+use waiter::repository::DynamoDbOrderRepository;
+
+#[instrument(name = "agent_caller_handler", skip(event, bedrock_agentruntime_client, config, shop), fields(req_id = %event.context.request_id))]
 async fn handler(
     event: LambdaEvent<ClientPrompt>,
     bedrock_agentruntime_client: &aws_sdk_bedrockagentruntime::Client,
-) -> Result<(), Error> {
+    config: &Config,
+    shop: Option<&Shop>,
+) -> Result<AgentReply, Error> {
     tracing::info!("AgentCaller handler invoked with payload: {:#?}", event);
 
-    let request_id = event.context.request_id;
-    let input_prompt = event.payload.input;
-    let enable_trace = std::env::var("ENABLE_TRACE")
-        .map(|val| val.parse::<bool>().unwrap_or(true))
-        .unwrap_or(true);
-    let Ok(model_id) = std::env::var("MODEL_ID") else {
-        return Err(r#"Missing "MODEL_ID" environment variable."#.into());
-    };
+    converse(
+        event.payload,
+        bedrock_agentruntime_client,
+        config,
+        shop,
+        None,
+    )
+    .await
+}
 
-    // Action: Ice Cream Maker
-    let Ok(ice_cream_maker_lambda) = std::env::var("ICE_CREAM_MAKER_LAMBDA") else {
-        return Err(r#"Missing "ICE_CREAM_MAKER_LAMBDA" environment variable "#.into());
-    };
-    let ice_cream_maker_action_group =
-        AgentActionGroup::builder()
-            .action_group_name("IceCreamMaker")
-            .action_group_executor(ActionGroupExecutor::Lambda(ice_cream_maker_lambda))
-            .description(
-                r"
-                ActionGroup that allows to manipulate the ice cream maker
-                ",
+/**
+ * Responds right away with a stream of server-sent events, which is written while the agent answers:
+ * every chunk of the answer, the traces when asked for, and finally the whole reply, or an error.
+ *
+ * Reference: https://docs.aws.amazon.com/lambda/latest/dg/configuration-response-streaming.html
+ */
+#[instrument(name = "agent_caller_streaming_handler", skip(event, bedrock_agentruntime_client, config, shop), fields(req_id = %event.context.request_id))]
+async fn streaming_handler(
+    event: LambdaEvent<ClientPrompt>,
+    bedrock_agentruntime_client: &aws_sdk_bedrockagentruntime::Client,
+    config: &Arc<Config>,
+    shop: Option<&Arc<Shop>>,
+) -> Result<Response<Body>, Error> {
+    tracing::info!("AgentCaller handler invoked with payload: {:#?}", event);
+
+    let (mut events, response) = EventStream::channel();
+    let bedrock_agentruntime_client = bedrock_agentruntime_client.clone();
+    let config = Arc::clone(config);
+    let shop = shop.cloned();
+
+    // The body is only sent once the response is returned, so the agent is invoked in the background
+    tokio::spawn(
+        async move {
+            let result = converse(
+                event.payload,
+                &bedrock_agentruntime_client,
+                &config,
+                shop.as_deref(),
+                Some(&mut events),
             )
-            .function_schema(FunctionSchema::Functions(vec![
-            FunctionDefinition::builder()
-                .name("PrepareIceCream")
-                .description(
-                    r"
-                    Manages the ice cream maker machine to make the ice creams of a given flavor
-                    ",
-                )
-                .parameters("flavor", ParameterDetail::builder()
-                .description("Flavor requested to be made with the ice cream maker machine")
-                .r#type(ParameterType::String)
-                .required(true)
-                .build()?)
-                .build()?,
-        ]))
-            .build()?;
-
-    // Action: Waiter
-    let Ok(waiter_lambda) = std::env::var("WAITER_LAMBDA") else {
-        return Err(r#"Missing "WAITER_LAMBDA" environment variable "#.into());
-    };
-    let waiter_api_schema_content = include_str!("../../waiter/schemas/waiter.yaml");
-    let waiter_api_schema = ApiSchema::Payload(waiter_api_schema_content.to_string());
-    let waiter_action_group = AgentActionGroup::builder()
-        .action_group_name("Waiter")
-        .action_group_executor(ActionGroupExecutor::Lambda(waiter_lambda))
-        .description(
-            r"
-            ActionGroup that allows to take orders and ice cream flavors to add to orders or remove from orders
-            ",
-        )
-        .api_schema(waiter_api_schema)
-        .build()?;
-
-    // Agent Invoke Inline
-    let invoke_response = bedrock_agentruntime_client
-        .invoke_inline_agent()
-        .session_id(request_id)
-        .enable_trace(enable_trace)
-        .input_text(input_prompt)
-        .foundation_model(model_id)
-        .instruction(AGENT_INSTRUCTION)
-        .action_groups(ice_cream_maker_action_group)
-        .action_groups(waiter_action_group)
-        .send()
-        .await?;
-
-    let mut response = invoke_response.completion;
-
-    // Consume chunks and traces from the stream
-    while let Some(event) = response.recv().await? {
-        match event {
-            InlineAgentResponseStream::Chunk(chunk) => {
-                if let Some(response_chunk) = chunk.bytes {
-                    let blob_text = String::from_utf8(response_chunk.into_inner())?;
-                    tracing::info!("Response Chunk: {:?}", &blob_text);
+            .await;
+
+            let sent = match result {
+                Ok(reply) => events.send(StreamEvent::Reply(&reply)).await,
+                Err(error) => {
+                    tracing::error!("Agent couldn't answer: {}", error);
+                    events
+                        .send(StreamEvent::Error {
+                            message: error.to_string(),
+                        })
+                        .await
                 }
+            };
+            if let Err(error) = sent {
+                tracing::error!("Couldn't write to the response stream: {}", error);
             }
-            /*
-             * Here I'm only logging Rationale traces.
-             * The Rationale object contains the reasoning of the agent given the user input.
-             * 
-             * Reference: https://docs.aws.amazon.com/bedrock/latest/userguide/trace-events.html
-             */
-            InlineAgentResponseStream::Trace(trace_part) => {
-                if let Some(Trace::OrchestrationTrace(OrchestrationTrace::Rationale(Rationale { text, .. }))) = trace_part.trace {
-                    tracing::info!("Rationale: {:?}", text);
+        }
+        .in_current_span(),
+    );
+
+    Ok(response)
+}
+
+/// Talks to the agent until it answers, writing the answer to `events` as it arrives when given.
+async fn converse(
+    mut prompt: ClientPrompt,
+    bedrock_agentruntime_client: &aws_sdk_bedrockagentruntime::Client,
+    config: &Config,
+    shop: Option<&Shop>,
+    mut events: Option<&mut EventStream>,
+) -> Result<AgentReply, Error> {
+    let session_id = prompt.take_session_id();
+    let ClientPrompt {
+        input: input_prompt,
+        session_id: _,
+        session_attributes,
+        prompt_session_attributes,
+        include_trace,
+        agent: agent_name,
+    } = prompt;
+    let Some(agent) = config.manifest.agent(agent_name.as_deref()) else {
+        return Err(format!(
+            "Agent {:?} is not defined in the manifest.",
+            agent_name.unwrap_or_default()
+        )
+        .into());
+    };
+    let action_groups = agent.action_groups()?;
+
+    let session = Session {
+        id: &session_id,
+        input_text: &input_prompt,
+        attributes: &session_attributes,
+        prompt_attributes: &prompt_session_attributes,
+    };
+    let mut session_state = InlineSessionState::builder()
+        .set_session_attributes(Some(session_attributes.clone()))
+        .set_prompt_session_attributes(Some(prompt_session_attributes.clone()))
+        .build();
+    let mut input_text = Some(input_prompt.clone());
+    let mut reply = AgentReply::new(session_id.clone());
+    if include_trace {
+        reply = reply.with_trace();
+    }
+
+    /*
+     * The agent is invoked again with the results of the action groups every time it returns control,
+     * until it answers.
+     *
+     * Reference: https://docs.aws.amazon.com/bedrock/latest/userguide/agents-returncontrol.html
+     */
+    loop {
+        // Agent Invoke Inline
+        let invoke_response = bedrock_agentruntime_client
+            .invoke_inline_agent()
+            .session_id(&session_id)
+            .inline_session_state(session_state)
+            .enable_trace(config.enable_trace)
+            .set_input_text(input_text.take())
+            .foundation_model(&agent.foundation_model)
+            .instruction(&agent.instruction)
+            .set_action_groups(Some(action_groups.clone()))
+            .send()
+            .await?;
+
+        let mut response = invoke_response.completion;
+        let mut return_control = None;
+
+        // Consume chunks and traces from the stream
+        while let Some(event) = response.recv().await? {
+            match event {
+                InlineAgentResponseStream::Chunk(chunk) => {
+                    let text = reply.push_chunk(chunk)?;
+                    if let Some(events) = events.as_deref_mut() {
+                        if !text.is_empty() {
+                            events.send(StreamEvent::Chunk { text }).await?;
+                        }
+                    }
+                }
+                /*
+                 * Every trace is logged as a structured record, it tells the reasoning of the agent,
+                 * the action groups it invoked and what they returned, and the prompts sent to the model.
+                 *
+                 * Reference: https://docs.aws.amazon.com/bedrock/latest/userguide/trace-events.html
+                 */
+                InlineAgentResponseStream::Trace(trace_part) => {
+                    if let Some(trace) = trace_part.trace {
+                        let event = TraceEvent::from(&trace);
+                        tracing::info!(trace = %serde_json::to_string(&event)?, "Agent trace");
+                        if let Some(events) = events.as_deref_mut() {
+                            if include_trace {
+                                events.send(StreamEvent::Trace(&event)).await?;
+                            }
+                        }
+                        reply.push_trace(event);
+                    }
+                }
+                InlineAgentResponseStream::ReturnControl(payload) => {
+                    return_control = Some(payload);
                 }
+                _ => {}
             }
-            _ => {}
         }
+
+        let Some(return_control) = return_control else {
+            break;
+        };
+        let Some(shop) = shop else {
+            return Err(
+                "The agent returned control, but action groups aren't run by this function.".into(),
+            );
+        };
+
+        let mut results = Vec::new();
+        for invocation_input in return_control.invocation_inputs() {
+            tracing::info!("Running returned invocation: {:?}", invocation_input);
+            results.push(shop.invoke(&session, invocation_input).await?);
+        }
+
+        session_state = InlineSessionState::builder()
+            .set_invocation_id(return_control.invocation_id)
+            .set_return_control_invocation_results(Some(results))
+            .set_session_attributes(Some(session_attributes.clone()))
+            .set_prompt_session_attributes(Some(prompt_session_attributes.clone()))
+            .build();
     }
 
-    Ok(())
+    tracing::info!("Reply: {:?}", serde_json::to_string(&reply)?);
+
+    Ok(reply)
 }
 
 #[tokio::main]
@@ -182,7 +235,35 @@ async fn main() -> Result<(), Error> {
 
     let sdk_config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
     let bedrock_agentruntime_client = aws_sdk_bedrockagentruntime::Client::new(&sdk_config);
-    let func = service_fn(|event| handler(event, &bedrock_agentruntime_client));
 
-    run(func).await
+    let config = Config::from_env().inspect_err(|error| tracing::error!("{error}"))?;
+
+    // Action groups set to return control are run by this function
+    let shop = config.shop.as_ref().map(|shop_config| {
+        let dynamodb_client = aws_sdk_dynamodb::Client::new(&sdk_config);
+        let repository =
+            DynamoDbOrderRepository::new(dynamodb_client, shop_config.table_name.clone());
+
+        Arc::new(Shop::new(repository, shop_config.flavor_catalog.clone()))
+    });
+    let config = Arc::new(config);
+
+    if config.stream_response {
+        let func = service_fn(|event| {
+            streaming_handler(event, &bedrock_agentruntime_client, &config, shop.as_ref())
+        });
+
+        run(func).await
+    } else {
+        let func = service_fn(|event| {
+            handler(
+                event,
+                &bedrock_agentruntime_client,
+                &config,
+                shop.as_deref(),
+            )
+        });
+
+        run(func).await
+    }
 }
@@ -0,0 +1,399 @@
+/**
+ * Declarative definition of the inline agents hosted by the agent caller: their instruction,
+ * foundation model and action groups.
+ *
+ * Reference:
+ * - https://docs.aws.amazon.com/bedrock/latest/userguide/agents-create-inline.html
+ * - https://docs.aws.amazon.com/bedrock/latest/APIReference/API_agent-runtime_AgentActionGroup.html
+ */
+use aws_sdk_bedrockagentruntime::types as bedrock;
+use lambda_runtime::Error;
+use serde::Deserialize;
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt,
+    path::{Path, PathBuf},
+};
+
+/// Manifest used when no "AGENT_MANIFEST" is configured, see [`crate::config::Config`].
+pub const DEFAULT_MANIFEST: &str = include_str!("../agents.yaml");
+
+/// Files referenced by [`DEFAULT_MANIFEST`], bundled in the binary since it is.
+const EMBEDDED_FILES: &[(&str, &str)] = &[(
+    "../waiter/schemas/waiter.yaml",
+    include_str!("../../waiter/schemas/waiter.yaml"),
+)];
+
+/// Bedrock rejects shorter instructions.
+const MIN_INSTRUCTION_LENGTH: usize = 40;
+
+const PARAMETER_TYPES: &[&str] = &["string", "number", "integer", "boolean", "array"];
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Manifest {
+    pub agents: Vec<AgentDefinition>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AgentDefinition {
+    pub name: String,
+    pub foundation_model: String,
+    /// What the agent is designed to do and how it should interact with users.
+    pub instruction: String,
+    #[serde(default)]
+    pub action_groups: Vec<ActionGroupDefinition>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ActionGroupDefinition {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(with = "serde_yaml::with::singleton_map")]
+    pub executor: Executor,
+    /// Set for action groups defined with a function details schema.
+    #[serde(default)]
+    pub functions: Vec<FunctionDefinition>,
+    /// Set for action groups defined with an OpenAPI schema.
+    #[serde(default, with = "serde_yaml::with::singleton_map")]
+    pub api_schema: Option<ApiSchemaSource>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Executor {
+    /// ARN of the lambda function the agent invokes.
+    Lambda(String),
+    /// The agent returns control to the agent caller, which runs the action group itself.
+    ReturnControl,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct FunctionDefinition {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub parameters: BTreeMap<String, ParameterDefinition>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ParameterDefinition {
+    pub r#type: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ApiSchemaSource {
+    /// File relative to the manifest, replaced by its content when the manifest is loaded.
+    Path(PathBuf),
+    Payload(String),
+    S3 {
+        bucket: String,
+        key: String,
+    },
+}
+
+/// Where the files referenced by a manifest are read from.
+pub enum Files<'a> {
+    /// The manifest is [`DEFAULT_MANIFEST`].
+    Embedded,
+    /// Directory of the manifest file.
+    Dir(&'a Path),
+}
+
+impl Manifest {
+    /// Loads the manifest file at the "AGENT_MANIFEST" variable, falling back to
+    /// [`DEFAULT_MANIFEST`] when it isn't set.
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self, ManifestError> {
+        match var("AGENT_MANIFEST") {
+            Some(path) => {
+                let path = PathBuf::from(path);
+                let source = std::fs::read_to_string(&path)
+                    .map_err(|error| ManifestError::Read(format!("{}: {error}", path.display())))?;
+                let dir = path.parent().unwrap_or(Path::new("."));
+
+                Self::load(&source, &Files::Dir(dir), var)
+            }
+            None => Self::load(DEFAULT_MANIFEST, &Files::Embedded, var),
+        }
+    }
+
+    /// Parses and validates a manifest, replacing `${NAME}` in models, lambda ARNs and API schema
+    /// locations by `env(NAME)`, and API schema paths by the content of the file.
+    ///
+    /// Every problem found is reported at once.
+    pub fn load(
+        source: &str,
+        files: &Files,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self, ManifestError> {
+        let mut manifest: Manifest = serde_yaml::from_str(source)
+            .map_err(|error| ManifestError::Parse(error.to_string()))?;
+        let mut errors = Vec::new();
+
+        if manifest.agents.is_empty() {
+            errors.push("no agents defined".to_string());
+        }
+
+        let mut agent_names = HashSet::new();
+        for agent in &mut manifest.agents {
+            if !agent_names.insert(agent.name.clone()) {
+                errors.push(format!("agent {:?} is defined more than once", agent.name));
+            }
+            agent.resolve(files, &env, &mut errors);
+            agent.validate(&mut errors);
+        }
+
+        if errors.is_empty() {
+            Ok(manifest)
+        } else {
+            Err(ManifestError::Invalid(errors))
+        }
+    }
+
+    /// Agent with the given name, or the first one defined when no name is given.
+    pub fn agent(&self, name: Option<&str>) -> Option<&AgentDefinition> {
+        match name {
+            Some(name) => self.agents.iter().find(|agent| agent.name == name),
+            None => self.agents.first(),
+        }
+    }
+
+    /// Whether any action group returns control to the agent caller.
+    pub fn returns_control(&self) -> bool {
+        self.agents
+            .iter()
+            .flat_map(|agent| &agent.action_groups)
+            .any(|action_group| action_group.executor == Executor::ReturnControl)
+    }
+}
+
+impl AgentDefinition {
+    fn resolve(
+        &mut self,
+        files: &Files,
+        env: &impl Fn(&str) -> Option<String>,
+        errors: &mut Vec<String>,
+    ) {
+        let context = format!("agent {:?}", self.name);
+        interpolate(&mut self.foundation_model, env, &context, errors);
+
+        for action_group in &mut self.action_groups {
+            let context = format!("{context}, action group {:?}", action_group.name);
+
+            if let Executor::Lambda(arn) = &mut action_group.executor {
+                interpolate(arn, env, &context, errors);
+            }
+
+            match &mut action_group.api_schema {
+                Some(ApiSchemaSource::Path(path)) => match read(files, path) {
+                    Ok(payload) => {
+                        action_group.api_schema = Some(ApiSchemaSource::Payload(payload))
+                    }
+                    Err(error) => errors.push(format!("{context}: {error}")),
+                },
+                Some(ApiSchemaSource::S3 { bucket, key }) => {
+                    interpolate(bucket, env, &context, errors);
+                    interpolate(key, env, &context, errors);
+                }
+                Some(ApiSchemaSource::Payload(_)) | None => {}
+            }
+        }
+    }
+
+    fn validate(&self, errors: &mut Vec<String>) {
+        let context = format!("agent {:?}", self.name);
+
+        if self.foundation_model.trim().is_empty() {
+            errors.push(format!("{context}: foundationModel is empty"));
+        }
+        if self.instruction.trim().len() < MIN_INSTRUCTION_LENGTH {
+            errors.push(format!(
+                "{context}: instruction must be at least {MIN_INSTRUCTION_LENGTH} characters long"
+            ));
+        }
+
+        let mut action_group_names = HashSet::new();
+        for action_group in &self.action_groups {
+            let context = format!("{context}, action group {:?}", action_group.name);
+
+            if !action_group_names.insert(&action_group.name) {
+                errors.push(format!("{context}: defined more than once"));
+            }
+            if matches!(&action_group.executor, Executor::Lambda(arn) if arn.trim().is_empty()) {
+                errors.push(format!("{context}: lambda ARN is empty"));
+            }
+            match (action_group.functions.is_empty(), &action_group.api_schema) {
+                (true, None) => errors.push(format!("{context}: expected functions or apiSchema")),
+                (false, Some(_)) => errors.push(format!(
+                    "{context}: expected either functions or apiSchema, not both"
+                )),
+                _ => {}
+            }
+
+            for function in &action_group.functions {
+                for (name, parameter) in &function.parameters {
+                    if !PARAMETER_TYPES.contains(&parameter.r#type.as_str()) {
+                        errors.push(format!(
+                            "{context}, function {:?}: parameter {name:?} has unsupported type {:?}, expected one of {}",
+                            function.name,
+                            parameter.r#type,
+                            PARAMETER_TYPES.join(", ")
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Action groups as sent to `InvokeInlineAgent`.
+    pub fn action_groups(&self) -> Result<Vec<bedrock::AgentActionGroup>, Error> {
+        self.action_groups
+            .iter()
+            .map(|action_group| {
+                let executor = match &action_group.executor {
+                    Executor::Lambda(arn) => bedrock::ActionGroupExecutor::Lambda(arn.clone()),
+                    Executor::ReturnControl => bedrock::ActionGroupExecutor::CustomControl(
+                        bedrock::CustomControlMethod::ReturnControl,
+                    ),
+                };
+                let mut builder = bedrock::AgentActionGroup::builder()
+                    .action_group_name(&action_group.name)
+                    .set_description(action_group.description.clone())
+                    .action_group_executor(executor);
+
+                if !action_group.functions.is_empty() {
+                    let functions = action_group
+                        .functions
+                        .iter()
+                        .map(function_definition)
+                        .collect::<Result<Vec<_>, Error>>()?;
+                    builder =
+                        builder.function_schema(bedrock::FunctionSchema::Functions(functions));
+                }
+
+                builder = match &action_group.api_schema {
+                    Some(ApiSchemaSource::Payload(payload)) => {
+                        builder.api_schema(bedrock::ApiSchema::Payload(payload.clone()))
+                    }
+                    Some(ApiSchemaSource::S3 { bucket, key }) => {
+                        builder.api_schema(bedrock::ApiSchema::S3(
+                            bedrock::S3Identifier::builder()
+                                .s3_bucket_name(bucket)
+                                .s3_object_key(key)
+                                .build(),
+                        ))
+                    }
+                    Some(ApiSchemaSource::Path(path)) => {
+                        return Err(format!(
+                            "API schema {} of action group {:?} wasn't loaded",
+                            path.display(),
+                            action_group.name
+                        )
+                        .into());
+                    }
+                    None => builder,
+                };
+
+                Ok(builder.build()?)
+            })
+            .collect()
+    }
+}
+
+fn function_definition(
+    function: &FunctionDefinition,
+) -> Result<bedrock::FunctionDefinition, Error> {
+    let mut builder = bedrock::FunctionDefinition::builder()
+        .name(&function.name)
+        .set_description(function.description.clone());
+
+    for (name, parameter) in &function.parameters {
+        builder = builder.parameters(
+            name,
+            bedrock::ParameterDetail::builder()
+                .r#type(bedrock::ParameterType::from(parameter.r#type.as_str()))
+                .set_description(parameter.description.clone())
+                .required(parameter.required)
+                .build()?,
+        );
+    }
+
+    Ok(builder.build()?)
+}
+
+/// Replaces every `${NAME}` in `value` by `env(NAME)`, reporting a `${` never closed.
+fn interpolate(
+    value: &mut String,
+    env: &impl Fn(&str) -> Option<String>,
+    context: &str,
+    errors: &mut Vec<String>,
+) {
+    let mut interpolated = String::with_capacity(value.len());
+    let mut rest = value.as_str();
+
+    while let Some(start) = rest.find("${") {
+        let Some(end) = rest[start..].find('}') else {
+            errors.push(format!("{context}: unterminated \"${{\" in {value:?}"));
+            break;
+        };
+        let name = &rest[start + 2..start + end];
+
+        interpolated.push_str(&rest[..start]);
+        match env(name) {
+            Some(replacement) => interpolated.push_str(&replacement),
+            None => {
+                errors.push(format!("{context}: missing {name:?} environment variable"));
+                interpolated.push_str(&rest[start..=start + end]);
+            }
+        }
+        rest = &rest[start + end + 1..];
+    }
+    interpolated.push_str(rest);
+
+    *value = interpolated;
+}
+
+fn read(files: &Files, path: &Path) -> Result<String, String> {
+    match files {
+        Files::Embedded => EMBEDDED_FILES
+            .iter()
+            .find(|(embedded, _)| Path::new(embedded) == path)
+            .map(|(_, content)| content.to_string())
+            .ok_or_else(|| format!("{} is not embedded", path.display())),
+        Files::Dir(dir) => std::fs::read_to_string(dir.join(path))
+            .map_err(|error| format!("{}: {error}", dir.join(path).display())),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ManifestError {
+    Read(String),
+    Parse(String),
+    /// Every problem found in the manifest.
+    Invalid(Vec<String>),
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ManifestError::Read(error) => write!(f, "Couldn't read the agent manifest: {error}"),
+            ManifestError::Parse(error) => write!(f, "Invalid agent manifest: {error}"),
+            ManifestError::Invalid(errors) => {
+                write!(f, "Invalid agent manifest: {}", errors.join("; "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for ManifestError {}
@@ -0,0 +1,148 @@
+/**
+ * Final answer of the agent, assembled from the events of the `InvokeInlineAgent` response stream.
+ *
+ * Reference:
+ * - https://docs.aws.amazon.com/bedrock/latest/APIReference/API_agent-runtime_InvokeInlineAgent.html
+ * - https://docs.aws.amazon.com/bedrock/latest/userguide/trace-events.html
+ */
+use crate::trace::TraceEvent;
+use aws_sdk_bedrockagentruntime::types as bedrock;
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentReply {
+    pub session_id: String,
+    /// Text of the answer, the concatenation of every chunk sent by the agent.
+    pub completion: String,
+    /// Action groups invoked by the agent, in order. Only known when traces are enabled.
+    pub invoked_action_groups: Vec<ActionGroupInvocation>,
+    pub citations: Vec<Citation>,
+    /// Every trace sent by the agent, only when the caller asks for it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace: Option<Vec<TraceEvent>>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionGroupInvocation {
+    pub action_group: String,
+    /// Set for action groups defined with a function details schema.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function: Option<String>,
+    /// Set for action groups defined with an OpenAPI schema.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http_method: Option<String>,
+}
+
+/// Part of the completion backed by retrieved sources.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Citation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    pub references: Vec<Reference>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Reference {
+    pub text: String,
+    /// S3 URI or web URL of the source.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+}
+
+impl AgentReply {
+    pub fn new(session_id: impl Into<String>) -> Self {
+        AgentReply {
+            session_id: session_id.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Appends a chunk of the answer and the citations attributed to it, returning the text appended.
+    pub fn push_chunk(
+        &mut self,
+        chunk: bedrock::InlineAgentPayloadPart,
+    ) -> Result<&str, std::string::FromUtf8Error> {
+        let start = self.completion.len();
+        if let Some(bytes) = chunk.bytes {
+            self.completion
+                .push_str(&String::from_utf8(bytes.into_inner())?);
+        }
+
+        if let Some(attribution) = chunk.attribution {
+            self.citations
+                .extend(attribution.citations().iter().map(Citation::from));
+        }
+
+        Ok(&self.completion[start..])
+    }
+
+    /// Keeps the traces sent by the agent, which are otherwise only used to know the
+    /// action groups it invoked.
+    pub fn with_trace(mut self) -> Self {
+        self.trace = Some(Vec::new());
+        self
+    }
+
+    /// Records the action group invoked in a trace, and the trace itself if asked to.
+    pub fn push_trace(&mut self, event: TraceEvent) {
+        if let Some(action_group) = event.action_group() {
+            self.invoked_action_groups.push(action_group.clone());
+        }
+
+        if let Some(trace) = &mut self.trace {
+            trace.push(event);
+        }
+    }
+}
+
+impl From<&bedrock::ActionGroupInvocationInput> for ActionGroupInvocation {
+    fn from(input: &bedrock::ActionGroupInvocationInput) -> Self {
+        ActionGroupInvocation {
+            action_group: input.action_group_name().unwrap_or_default().to_string(),
+            function: input.function().map(str::to_string),
+            api_path: input.api_path().map(str::to_string),
+            http_method: input.verb().map(str::to_string),
+        }
+    }
+}
+
+impl From<&bedrock::Citation> for Citation {
+    fn from(citation: &bedrock::Citation) -> Self {
+        Citation {
+            text: citation
+                .generated_response_part()
+                .and_then(|part| part.text_response_part())
+                .and_then(|part| part.text())
+                .map(str::to_string),
+            references: citation
+                .retrieved_references()
+                .iter()
+                .map(|reference| Reference {
+                    text: reference
+                        .content()
+                        .map(|content| content.text.clone())
+                        .unwrap_or_default(),
+                    location: reference.location().and_then(location),
+                })
+                .collect(),
+        }
+    }
+}
+
+fn location(location: &bedrock::RetrievalResultLocation) -> Option<String> {
+    location
+        .s3_location()
+        .and_then(|s3_location| s3_location.uri())
+        .or_else(|| {
+            location
+                .web_location()
+                .and_then(|web_location| web_location.url())
+        })
+        .map(str::to_string)
+}
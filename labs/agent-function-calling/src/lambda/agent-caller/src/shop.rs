@@ -0,0 +1,244 @@
+/**
+ * Runs the action groups of the ice cream shop inside this lambda function, for agents whose
+ * action groups return control to the caller instead of invoking a lambda function.
+ *
+ * Reference:
+ * - https://docs.aws.amazon.com/bedrock/latest/userguide/agents-returncontrol.html
+ */
+use aws_sdk_bedrockagentruntime::types as bedrock;
+use bedrock_agent_events::{
+    api::{AgentApiRequest, Property, RequestBody, RequestBodyContentType},
+    function::{AgentRequest, ResponseState, ResponseType},
+    Agent, ParameterType, Parameters, MESSAGE_VERSION,
+};
+use ice_cream_maker::{catalog::FlavorCatalog, machine::SimulatedMachine};
+use lambda_runtime::{Context, Error, LambdaEvent};
+use std::collections::HashMap;
+use waiter::{
+    repository::{DynamoDbOrderRepository, OrderRepository},
+    router::Router,
+    routes,
+};
+
+/// Names the action groups and functions the shop runs are given in `agents.yaml`.
+pub const WAITER_ACTION_GROUP: &str = "Waiter";
+pub const ICE_CREAM_MAKER_ACTION_GROUP: &str = "IceCreamMaker";
+pub const PREPARE_ICE_CREAM_FUNCTION: &str = "PrepareIceCream";
+
+/// Conversation the agent returned control from.
+pub struct Session<'a> {
+    pub id: &'a str,
+    pub input_text: &'a str,
+    pub attributes: &'a HashMap<String, String>,
+    pub prompt_attributes: &'a HashMap<String, String>,
+}
+
+/// The waiter and ice cream maker action groups, with the same logic as their lambda functions.
+///
+/// Orders are kept in DynamoDB like the waiter does, any other repository works too.
+pub struct Shop<R = DynamoDbOrderRepository> {
+    router: Router<R>,
+    repository: R,
+    catalog: FlavorCatalog,
+    machine: SimulatedMachine,
+}
+
+impl<R: OrderRepository + Sync> Shop<R> {
+    pub fn new(repository: R, catalog: FlavorCatalog) -> Self {
+        Shop {
+            router: routes::router(),
+            repository,
+            catalog,
+            machine: SimulatedMachine,
+        }
+    }
+
+    /// Runs the action group invocation requested by the agent and returns its result.
+    ///
+    /// Fails for action groups and functions other than the waiter and the ice cream maker ones,
+    /// since the shop can't run them.
+    pub async fn invoke(
+        &self,
+        session: &Session<'_>,
+        input: &bedrock::InvocationInputMember,
+    ) -> Result<bedrock::InvocationResultMember, Error> {
+        match input {
+            bedrock::InvocationInputMember::ApiInvocationInput(input)
+                if input.action_group() == WAITER_ACTION_GROUP =>
+            {
+                let request = api_request(session, input);
+                let response = waiter::handler(
+                    LambdaEvent::new(request, Context::default()),
+                    &self.router,
+                    &self.repository,
+                )
+                .await?
+                .response;
+
+                let result = bedrock::ApiResult::builder()
+                    .action_group(response.action_group)
+                    .api_path(response.api_path)
+                    .http_method(response.http_method)
+                    .http_status_code(response.http_status_code)
+                    .set_response_body(Some(content_bodies(
+                        response
+                            .response_body
+                            .content
+                            .into_iter()
+                            .map(|(content_type, content)| (content_type, content.body)),
+                    )))
+                    .build()?;
+
+                Ok(bedrock::InvocationResultMember::ApiResult(result))
+            }
+            bedrock::InvocationInputMember::FunctionInvocationInput(input)
+                if input.action_group() == ICE_CREAM_MAKER_ACTION_GROUP
+                    && input.function() == Some(PREPARE_ICE_CREAM_FUNCTION) =>
+            {
+                let request = function_request(session, input);
+                let response = ice_cream_maker::handler(
+                    LambdaEvent::new(request, Context::default()),
+                    &self.catalog,
+                    &self.machine,
+                )
+                .await?
+                .response;
+
+                let (response_state, response_body) = match response.function_response.response_type
+                {
+                    ResponseType::Body { response_body } => (None, Some(response_body)),
+                    ResponseType::State {
+                        response_state,
+                        response_body,
+                    } => (Some(state(response_state)), response_body),
+                };
+                let result = bedrock::FunctionResult::builder()
+                    .action_group(response.action_group)
+                    .function(response.function)
+                    .set_response_state(response_state)
+                    .set_response_body(response_body.map(|response_body| {
+                        content_bodies(
+                            response_body
+                                .content
+                                .into_iter()
+                                .map(|(content_type, content)| (content_type, content.body)),
+                        )
+                    }))
+                    .build()?;
+
+                Ok(bedrock::InvocationResultMember::FunctionResult(result))
+            }
+            bedrock::InvocationInputMember::ApiInvocationInput(input) => Err(format!(
+                "Unknown API action group {:?}, expected {WAITER_ACTION_GROUP:?}",
+                input.action_group()
+            )
+            .into()),
+            bedrock::InvocationInputMember::FunctionInvocationInput(input) => Err(format!(
+                "Unknown function {:?} of action group {:?}, expected {PREPARE_ICE_CREAM_FUNCTION:?} of {ICE_CREAM_MAKER_ACTION_GROUP:?}",
+                input.function().unwrap_or_default(),
+                input.action_group()
+            )
+            .into()),
+            unsupported => Err(format!("Unsupported invocation input: {unsupported:?}").into()),
+        }
+    }
+}
+
+/// Agent reported to the action groups, inline agents have no name, id or alias of their own.
+fn inline_agent() -> Agent {
+    Agent {
+        name: "InlineAgent".to_string(),
+        id: "INLINE_AGENT".to_string(),
+        alias: "INLINE_AGENT_ALIAS".to_string(),
+        version: "DRAFT".to_string(),
+    }
+}
+
+fn api_request(session: &Session<'_>, input: &bedrock::ApiInvocationInput) -> AgentApiRequest {
+    AgentApiRequest {
+        message_version: MESSAGE_VERSION.to_string(),
+        agent: inline_agent(),
+        input_text: session.input_text.to_string(),
+        session_id: session.id.to_string(),
+        action_group: input.action_group().to_string(),
+        api_path: input.api_path().unwrap_or_default().to_string(),
+        http_method: input.http_method().unwrap_or_default().to_string(),
+        parameters: input
+            .parameters()
+            .iter()
+            .map(|parameter| parameters(parameter.name(), parameter.r#type(), parameter.value()))
+            .collect(),
+        session_attributes: session.attributes.clone(),
+        prompt_session_attributes: session.prompt_attributes.clone(),
+        request_body: input
+            .request_body()
+            .and_then(|request_body| request_body.content())
+            .map(|content| RequestBody {
+                content: content
+                    .iter()
+                    .map(|(content_type, properties)| {
+                        let properties = properties
+                            .properties()
+                            .iter()
+                            .map(|property| Property {
+                                name: property.name().unwrap_or_default().to_string(),
+                                r#type: property.r#type().unwrap_or("string").to_string(),
+                                value: property.value().unwrap_or_default().to_string(),
+                            })
+                            .collect();
+
+                        (content_type.clone(), RequestBodyContentType { properties })
+                    })
+                    .collect(),
+            }),
+    }
+}
+
+fn function_request(
+    session: &Session<'_>,
+    input: &bedrock::FunctionInvocationInput,
+) -> AgentRequest {
+    AgentRequest {
+        message_version: MESSAGE_VERSION.to_string(),
+        agent: inline_agent(),
+        input_text: session.input_text.to_string(),
+        session_id: session.id.to_string(),
+        action_group: input.action_group().to_string(),
+        function: input.function().unwrap_or_default().to_string(),
+        parameters: input
+            .parameters()
+            .iter()
+            .map(|parameter| parameters(parameter.name(), parameter.r#type(), parameter.value()))
+            .collect(),
+        session_attributes: session.attributes.clone(),
+        prompt_session_attributes: session.prompt_attributes.clone(),
+    }
+}
+
+fn parameters(name: Option<&str>, r#type: Option<&str>, value: Option<&str>) -> Parameters {
+    Parameters {
+        name: name.unwrap_or_default().to_string(),
+        r#type: ParameterType::from(r#type.unwrap_or("string").to_string()),
+        value: value.unwrap_or_default().to_string(),
+    }
+}
+
+fn content_bodies(
+    content: impl Iterator<Item = (String, String)>,
+) -> HashMap<String, bedrock::ContentBody> {
+    content
+        .map(|(content_type, body)| {
+            (
+                content_type,
+                bedrock::ContentBody::builder().body(body).build(),
+            )
+        })
+        .collect()
+}
+
+fn state(response_state: ResponseState) -> bedrock::ResponseState {
+    match response_state {
+        ResponseState::Failure => bedrock::ResponseState::Failure,
+        ResponseState::Reprompt => bedrock::ResponseState::Reprompt,
+    }
+}
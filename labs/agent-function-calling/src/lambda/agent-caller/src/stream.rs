@@ -0,0 +1,86 @@
+/**
+ * Server-sent events written to the caller while the agent answers, when the agent caller runs with
+ * response streaming, so chat UIs can render the answer as it is generated.
+ *
+ * Reference:
+ * - https://docs.aws.amazon.com/lambda/latest/dg/configuration-response-streaming.html
+ * - https://html.spec.whatwg.org/multipage/server-sent-events.html#event-stream-interpretation
+ */
+use crate::{reply::AgentReply, trace::TraceEvent};
+use http::{header, HeaderValue};
+use lambda_runtime::{
+    streaming::{channel, Body, Response, Sender},
+    Error,
+};
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum StreamEvent<'a> {
+    /// Text of the answer, as soon as the agent sends it.
+    Chunk { text: &'a str },
+    /// Trace sent by the agent, only when the caller asks for them.
+    Trace(&'a TraceEvent),
+    /// The whole reply, once the agent has answered. It is always the last event of the stream.
+    Reply(&'a AgentReply),
+    /// Why the agent couldn't answer. It is the last event of the stream too.
+    Error { message: String },
+}
+
+impl StreamEvent<'_> {
+    pub fn name(&self) -> &'static str {
+        match self {
+            StreamEvent::Chunk { .. } => "chunk",
+            StreamEvent::Trace(_) => "trace",
+            StreamEvent::Reply(_) => "reply",
+            StreamEvent::Error { .. } => "error",
+        }
+    }
+
+    /// Formats the event as a server-sent event, with its data in JSON.
+    pub fn to_sse(&self) -> Result<String, serde_json::Error> {
+        Ok(sse(self.name(), &serde_json::to_string(self)?))
+    }
+}
+
+/// Frames `data` as a server-sent event named `event`, ended by a blank line.
+///
+/// Each line of `data` goes in its own `data:` field, which clients join back with line feeds.
+pub fn sse(event: &str, data: &str) -> String {
+    let mut frame = format!("event: {event}\n");
+    for line in data.replace("\r\n", "\n").split(['\n', '\r']) {
+        frame.push_str("data: ");
+        frame.push_str(line);
+        frame.push('\n');
+    }
+    frame.push('\n');
+
+    frame
+}
+
+/// Writing half of a streamed response.
+pub struct EventStream {
+    sender: Sender,
+}
+
+impl EventStream {
+    /// Creates the stream of events along with the response that sends them to the caller.
+    pub fn channel() -> (Self, Response<Body>) {
+        let (sender, body) = channel();
+        let mut response = Response::from(body);
+        response.metadata_prelude.headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("text/event-stream"),
+        );
+        response
+            .metadata_prelude
+            .headers
+            .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+
+        (EventStream { sender }, response)
+    }
+
+    pub async fn send(&mut self, event: StreamEvent<'_>) -> Result<(), Error> {
+        Ok(self.sender.send_data(event.to_sse()?.into()).await?)
+    }
+}
@@ -0,0 +1,273 @@
+/**
+ * Serializable model of the traces sent by the agent while it orchestrates an answer,
+ * so they can be logged and returned to the caller.
+ *
+ * Reference:
+ * - https://docs.aws.amazon.com/bedrock/latest/userguide/trace-events.html
+ */
+use crate::reply::ActionGroupInvocation;
+use aws_sdk_bedrockagentruntime::types as bedrock;
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum TraceEvent {
+    /// Reasoning of the agent before its next step.
+    Rationale {
+        trace_id: Option<String>,
+        text: Option<String>,
+    },
+    /// The agent calls an action group or queries a knowledge base.
+    InvocationInput {
+        trace_id: Option<String>,
+        invocation_type: Option<String>,
+        action_group: Option<ActionGroupInvocation>,
+        parameters: Vec<TraceParameter>,
+        knowledge_base_query: Option<String>,
+    },
+    /// Result of an invocation, or the final answer of the agent.
+    Observation {
+        trace_id: Option<String>,
+        observation_type: Option<String>,
+        action_group_output: Option<String>,
+        retrieved_references: usize,
+        final_response: Option<String>,
+        reprompt_response: Option<String>,
+    },
+    /// Prompt sent to the model at the given step.
+    ModelInvocationInput {
+        step: Step,
+        trace_id: Option<String>,
+        prompt_type: Option<String>,
+        text: Option<String>,
+    },
+    /// Response of the model at the given step.
+    ModelInvocationOutput {
+        step: Step,
+        trace_id: Option<String>,
+        raw_response: Option<String>,
+        /// Response parsed by the agent at post-processing.
+        parsed_response: Option<String>,
+        /// Whether pre-processing deemed the input valid, and why.
+        is_valid: Option<bool>,
+        rationale: Option<String>,
+    },
+    /// Event of an agent with custom orchestration, e.g. an output of its orchestration lambda.
+    CustomOrchestration {
+        trace_id: Option<String>,
+        text: Option<String>,
+    },
+    Guardrail {
+        trace_id: Option<String>,
+        action: Option<String>,
+    },
+    Failure {
+        trace_id: Option<String>,
+        reason: Option<String>,
+    },
+    /// Trace the SDK doesn't know, sent by a newer version of the service.
+    Unsupported { debug: String },
+}
+
+/// Orchestration step of the agent a model invocation belongs to.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Step {
+    PreProcessing,
+    Orchestration,
+    PostProcessing,
+    /// Routing of the input to a collaborator agent.
+    RoutingClassifier,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceParameter {
+    pub name: Option<String>,
+    pub r#type: Option<String>,
+    pub value: Option<String>,
+}
+
+impl TraceEvent {
+    /// Action group the agent invoked, if this is the trace of such invocation.
+    pub fn action_group(&self) -> Option<&ActionGroupInvocation> {
+        match self {
+            TraceEvent::InvocationInput { action_group, .. } => action_group.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+impl From<&bedrock::Trace> for TraceEvent {
+    fn from(trace: &bedrock::Trace) -> Self {
+        match trace {
+            bedrock::Trace::OrchestrationTrace(trace) => orchestration(trace),
+            bedrock::Trace::PreProcessingTrace(
+                bedrock::PreProcessingTrace::ModelInvocationInput(input),
+            ) => model_input(Step::PreProcessing, input),
+            bedrock::Trace::PreProcessingTrace(
+                bedrock::PreProcessingTrace::ModelInvocationOutput(output),
+            ) => TraceEvent::ModelInvocationOutput {
+                step: Step::PreProcessing,
+                trace_id: owned(output.trace_id()),
+                raw_response: owned(output.raw_response().and_then(|raw| raw.content())),
+                parsed_response: None,
+                is_valid: output
+                    .parsed_response()
+                    .and_then(|parsed| parsed.is_valid()),
+                rationale: owned(
+                    output
+                        .parsed_response()
+                        .and_then(|parsed| parsed.rationale()),
+                ),
+            },
+            bedrock::Trace::PostProcessingTrace(
+                bedrock::PostProcessingTrace::ModelInvocationInput(input),
+            ) => model_input(Step::PostProcessing, input),
+            bedrock::Trace::PostProcessingTrace(
+                bedrock::PostProcessingTrace::ModelInvocationOutput(output),
+            ) => TraceEvent::ModelInvocationOutput {
+                step: Step::PostProcessing,
+                trace_id: owned(output.trace_id()),
+                raw_response: owned(output.raw_response().and_then(|raw| raw.content())),
+                parsed_response: owned(output.parsed_response().and_then(|parsed| parsed.text())),
+                is_valid: None,
+                rationale: None,
+            },
+            bedrock::Trace::GuardrailTrace(guardrail) => TraceEvent::Guardrail {
+                trace_id: owned(guardrail.trace_id()),
+                action: owned(guardrail.action().map(|action| action.as_str())),
+            },
+            bedrock::Trace::FailureTrace(failure) => TraceEvent::Failure {
+                trace_id: owned(failure.trace_id()),
+                reason: owned(failure.failure_reason()),
+            },
+            bedrock::Trace::CustomOrchestrationTrace(custom) => TraceEvent::CustomOrchestration {
+                trace_id: owned(custom.trace_id()),
+                text: owned(custom.event().and_then(|event| event.text())),
+            },
+            bedrock::Trace::RoutingClassifierTrace(trace) => routing_classifier(trace),
+            unsupported => TraceEvent::Unsupported {
+                debug: format!("{unsupported:?}"),
+            },
+        }
+    }
+}
+
+fn orchestration(trace: &bedrock::OrchestrationTrace) -> TraceEvent {
+    match trace {
+        bedrock::OrchestrationTrace::Rationale(rationale) => TraceEvent::Rationale {
+            trace_id: owned(rationale.trace_id()),
+            text: owned(rationale.text()),
+        },
+        bedrock::OrchestrationTrace::InvocationInput(input) => invocation_input(input),
+        bedrock::OrchestrationTrace::Observation(output) => observation(output),
+        bedrock::OrchestrationTrace::ModelInvocationInput(input) => {
+            model_input(Step::Orchestration, input)
+        }
+        bedrock::OrchestrationTrace::ModelInvocationOutput(output) => {
+            TraceEvent::ModelInvocationOutput {
+                step: Step::Orchestration,
+                trace_id: owned(output.trace_id()),
+                raw_response: owned(output.raw_response().and_then(|raw| raw.content())),
+                parsed_response: None,
+                is_valid: None,
+                rationale: None,
+            }
+        }
+        unsupported => TraceEvent::Unsupported {
+            debug: format!("{unsupported:?}"),
+        },
+    }
+}
+
+fn routing_classifier(trace: &bedrock::RoutingClassifierTrace) -> TraceEvent {
+    match trace {
+        bedrock::RoutingClassifierTrace::InvocationInput(input) => invocation_input(input),
+        bedrock::RoutingClassifierTrace::Observation(output) => observation(output),
+        bedrock::RoutingClassifierTrace::ModelInvocationInput(input) => {
+            model_input(Step::RoutingClassifier, input)
+        }
+        bedrock::RoutingClassifierTrace::ModelInvocationOutput(output) => {
+            TraceEvent::ModelInvocationOutput {
+                step: Step::RoutingClassifier,
+                trace_id: owned(output.trace_id()),
+                raw_response: owned(output.raw_response().and_then(|raw| raw.content())),
+                parsed_response: None,
+                is_valid: None,
+                rationale: None,
+            }
+        }
+        unsupported => TraceEvent::Unsupported {
+            debug: format!("{unsupported:?}"),
+        },
+    }
+}
+
+fn invocation_input(input: &bedrock::InvocationInput) -> TraceEvent {
+    let action_group_input = input.action_group_invocation_input();
+
+    TraceEvent::InvocationInput {
+        trace_id: owned(input.trace_id()),
+        invocation_type: owned(input.invocation_type().map(|r#type| r#type.as_str())),
+        action_group: action_group_input.map(ActionGroupInvocation::from),
+        parameters: action_group_input
+            .map(|action_group_input| action_group_input.parameters())
+            .unwrap_or_default()
+            .iter()
+            .map(|parameter| TraceParameter {
+                name: owned(parameter.name()),
+                r#type: owned(parameter.r#type()),
+                value: owned(parameter.value()),
+            })
+            .collect(),
+        knowledge_base_query: owned(
+            input
+                .knowledge_base_lookup_input()
+                .and_then(|lookup| lookup.text()),
+        ),
+    }
+}
+
+fn observation(observation: &bedrock::Observation) -> TraceEvent {
+    TraceEvent::Observation {
+        trace_id: owned(observation.trace_id()),
+        observation_type: owned(observation.r#type().map(|r#type| r#type.as_str())),
+        action_group_output: owned(
+            observation
+                .action_group_invocation_output()
+                .and_then(|output| output.text()),
+        ),
+        retrieved_references: observation
+            .knowledge_base_lookup_output()
+            .map(|output| output.retrieved_references().len())
+            .unwrap_or_default(),
+        final_response: owned(
+            observation
+                .final_response()
+                .and_then(|response| response.text()),
+        ),
+        reprompt_response: owned(
+            observation
+                .reprompt_response()
+                .and_then(|response| response.text()),
+        ),
+    }
+}
+
+fn model_input(step: Step, input: &bedrock::ModelInvocationInput) -> TraceEvent {
+    TraceEvent::ModelInvocationInput {
+        step,
+        trace_id: owned(input.trace_id()),
+        prompt_type: owned(input.r#type().map(|r#type| r#type.as_str())),
+        text: owned(input.text()),
+    }
+}
+
+fn owned(value: Option<&str>) -> Option<String> {
+    value.map(str::to_string)
+}
@@ -1,6 +1,33 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
 
 #[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct ClientPrompt {
     pub input: String,
-}
\ No newline at end of file
+    /// Name of the agent in the manifest to talk to, the first one defined when it isn't given.
+    #[serde(default)]
+    pub agent: Option<String>,
+    /// Session to continue, a new one is started when it isn't given.
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Attributes persisted over the whole session.
+    #[serde(default)]
+    pub session_attributes: HashMap<String, String>,
+    /// Attributes persisted for this turn of the conversation only.
+    #[serde(default)]
+    pub prompt_session_attributes: HashMap<String, String>,
+    /// Returns the traces sent by the agent along with its answer.
+    #[serde(default)]
+    pub include_trace: bool,
+}
+
+impl ClientPrompt {
+    /// Takes the session to continue, or starts a new one when the prompt doesn't give any.
+    pub fn take_session_id(&mut self) -> String {
+        self.session_id
+            .take()
+            .unwrap_or_else(|| Uuid::new_v4().to_string())
+    }
+}
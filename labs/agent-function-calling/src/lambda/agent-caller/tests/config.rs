@@ -0,0 +1,111 @@
+use agent_caller::config::{Config, ConfigError};
+use std::collections::HashMap;
+
+fn vars(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let mut all: HashMap<String, String> = [
+        ("MODEL_ID", "anthropic.claude-3-sonnet-20240229-v1:0"),
+        (
+            "ICE_CREAM_MAKER_LAMBDA",
+            "arn:aws:lambda:us-east-1:123456789012:function:maker",
+        ),
+        (
+            "WAITER_LAMBDA",
+            "arn:aws:lambda:us-east-1:123456789012:function:waiter",
+        ),
+    ]
+    .iter()
+    .map(|(name, value)| (name.to_string(), value.to_string()))
+    .collect();
+    all.extend(
+        vars.iter()
+            .map(|(name, value)| (name.to_string(), value.to_string())),
+    );
+
+    move |name| all.get(name).cloned()
+}
+
+fn return_control_manifest() -> String {
+    format!("{}/tests/fixtures/agents.yaml", env!("CARGO_MANIFEST_DIR"))
+}
+
+#[test]
+fn uses_defaults_when_flags_are_not_set() {
+    let config = Config::from_vars(vars(&[])).unwrap();
+
+    assert!(config.enable_trace);
+    assert!(!config.stream_response);
+    assert_eq!(config.manifest.agent(None).unwrap().name, "IceCreamShop");
+}
+
+#[test]
+fn reads_flags() {
+    let config = Config::from_vars(vars(&[
+        ("ENABLE_TRACE", "false"),
+        ("STREAM_RESPONSE", "true"),
+    ]))
+    .unwrap();
+
+    assert!(!config.enable_trace);
+    assert!(config.stream_response);
+}
+
+#[test]
+fn reports_every_invalid_flag() {
+    assert_eq!(
+        Config::from_vars(vars(&[("ENABLE_TRACE", "yes"), ("STREAM_RESPONSE", "1")])),
+        Err(ConfigError(vec![
+            r#"Invalid "ENABLE_TRACE" environment variable "yes", expected "true" or "false"."#
+                .to_string(),
+            r#"Invalid "STREAM_RESPONSE" environment variable "1", expected "true" or "false"."#
+                .to_string(),
+        ]))
+    );
+}
+
+#[test]
+fn needs_no_shop_when_agents_invoke_lambdas() {
+    let config = Config::from_vars(vars(&[])).unwrap();
+
+    assert_eq!(config.shop, None);
+}
+
+#[test]
+fn needs_the_shop_when_agents_return_control() {
+    let manifest = return_control_manifest();
+
+    assert_eq!(
+        Config::from_vars(vars(&[("AGENT_MANIFEST", &manifest)])),
+        Err(ConfigError(vec![
+            r#"Missing "TABLE_NAME" environment variable."#.to_string()
+        ]))
+    );
+
+    let config = Config::from_vars(vars(&[
+        ("AGENT_MANIFEST", &manifest),
+        ("TABLE_NAME", "orders"),
+    ]))
+    .unwrap();
+    let shop = config.shop.expect("shop configuration");
+    assert_eq!(shop.table_name, "orders");
+    assert!(shop.flavor_catalog.find("Vanilla").is_some());
+}
+
+#[test]
+fn reports_manifest_and_shop_problems_together() {
+    let manifest = return_control_manifest();
+
+    assert_eq!(
+        Config::from_vars(vars(&[
+            ("AGENT_MANIFEST", &manifest),
+            ("TABLE_NAME", "orders"),
+            ("FLAVOR_CATALOG", "flavors: []"),
+            ("ENABLE_TRACE", "on"),
+        ])),
+        Err(ConfigError(vec![
+            r#"Invalid "ENABLE_TRACE" environment variable "on", expected "true" or "false"."#
+                .to_string(),
+            r#""FLAVOR_CATALOG" environment variable: Invalid flavor catalog: no flavors defined."#
+                .to_string(),
+        ]))
+    );
+}
@@ -0,0 +1,228 @@
+use agent_caller::manifest::{ApiSchemaSource, Executor, Files, Manifest, ManifestError};
+use std::collections::HashMap;
+
+const INSTRUCTION: &str = "You take ice cream orders and prepare the ice creams ordered.";
+
+fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let vars: HashMap<String, String> = vars
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+
+    move |name| vars.get(name).cloned()
+}
+
+fn default_env() -> impl Fn(&str) -> Option<String> {
+    env(&[
+        ("MODEL_ID", "anthropic.claude-3-sonnet-20240229-v1:0"),
+        (
+            "ICE_CREAM_MAKER_LAMBDA",
+            "arn:aws:lambda:us-east-1:123456789012:function:maker",
+        ),
+        (
+            "WAITER_LAMBDA",
+            "arn:aws:lambda:us-east-1:123456789012:function:waiter",
+        ),
+    ])
+}
+
+/// Manifest with one agent using the given model and a lambda action group with the given ARN.
+fn manifest(name: &str, foundation_model: &str, lambda: &str) -> String {
+    format!(
+        r#"
+agents:
+  - name: {name}
+    foundationModel: "{foundation_model}"
+    instruction: {INSTRUCTION}
+    actionGroups:
+      - name: IceCreamMaker
+        executor:
+          lambda: "{lambda}"
+        functions:
+          - name: PrepareIceCream
+"#
+    )
+}
+
+fn errors(result: Result<Manifest, ManifestError>) -> Vec<String> {
+    match result {
+        Err(ManifestError::Invalid(errors)) => errors,
+        other => panic!("expected an invalid manifest, got {other:?}"),
+    }
+}
+
+#[test]
+fn loads_embedded_manifest() {
+    let manifest = Manifest::from_vars(default_env()).expect("valid manifest");
+
+    let agent = manifest.agent(None).expect("an agent");
+    assert_eq!(agent.name, "IceCreamShop");
+    assert_eq!(
+        agent.foundation_model,
+        "anthropic.claude-3-sonnet-20240229-v1:0"
+    );
+    assert_eq!(
+        agent.action_groups[0].executor,
+        Executor::Lambda("arn:aws:lambda:us-east-1:123456789012:function:maker".to_string())
+    );
+    assert!(matches!(
+        &agent.action_groups[1].api_schema,
+        Some(ApiSchemaSource::Payload(payload)) if payload.contains("/start-order")
+    ));
+    assert!(!manifest.returns_control());
+    assert_eq!(agent.action_groups().expect("action groups").len(), 2);
+}
+
+#[test]
+fn loads_manifest_file_with_schemas_next_to_it() {
+    let path = format!("{}/tests/fixtures/agents.yaml", env!("CARGO_MANIFEST_DIR"));
+    let vars = env(&[
+        ("AGENT_MANIFEST", &path),
+        ("MODEL_ID", "amazon.nova-pro-v1:0"),
+    ]);
+
+    let manifest = Manifest::from_vars(vars).expect("valid manifest");
+
+    let agent = manifest.agent(Some("ReturnControlShop")).expect("an agent");
+    assert_eq!(
+        agent.action_groups[1].api_schema,
+        Some(ApiSchemaSource::Payload(
+            include_str!("fixtures/menu.yaml").to_string()
+        ))
+    );
+    assert!(manifest.returns_control());
+    assert!(manifest.agent(Some("IceCreamShop")).is_none());
+}
+
+#[test]
+fn fails_to_read_missing_manifest_file() {
+    let vars = env(&[("AGENT_MANIFEST", "/nonexistent/agents.yaml")]);
+
+    assert!(matches!(
+        Manifest::from_vars(vars),
+        Err(ManifestError::Read(error)) if error.starts_with("/nonexistent/agents.yaml")
+    ));
+}
+
+#[test]
+fn interpolates_environment_variables() {
+    let source = manifest(
+        "Shop",
+        "${MODEL_PREFIX}.claude-${MODEL_VERSION}",
+        "arn:aws:lambda:${REGION}:123456789012:function:maker",
+    );
+    let vars = env(&[
+        ("MODEL_PREFIX", "anthropic"),
+        ("MODEL_VERSION", "3-haiku"),
+        ("REGION", "eu-west-1"),
+    ]);
+
+    let manifest = Manifest::load(&source, &Files::Embedded, vars).expect("valid manifest");
+
+    let agent = manifest.agent(None).unwrap();
+    assert_eq!(agent.foundation_model, "anthropic.claude-3-haiku");
+    assert_eq!(
+        agent.action_groups[0].executor,
+        Executor::Lambda("arn:aws:lambda:eu-west-1:123456789012:function:maker".to_string())
+    );
+}
+
+#[test]
+fn reports_every_missing_variable() {
+    let source = manifest("Shop", "${MODEL_ID}", "${MAKER_LAMBDA}");
+
+    assert_eq!(
+        errors(Manifest::load(&source, &Files::Embedded, env(&[]))),
+        vec![
+            r#"agent "Shop": missing "MODEL_ID" environment variable"#,
+            r#"agent "Shop", action group "IceCreamMaker": missing "MAKER_LAMBDA" environment variable"#,
+        ]
+    );
+}
+
+#[test]
+fn reports_unterminated_variables() {
+    let source = manifest(
+        "Shop",
+        "${MODEL_ID",
+        "arn:aws:lambda:us-east-1:123456789012:function:maker",
+    );
+
+    assert_eq!(
+        errors(Manifest::load(&source, &Files::Embedded, default_env())),
+        vec![r#"agent "Shop": unterminated "${" in "${MODEL_ID""#]
+    );
+}
+
+#[test]
+fn rejects_empty_lambda_arns() {
+    let source = manifest("Shop", "amazon.nova-pro-v1:0", "${MAKER_LAMBDA}");
+    let vars = env(&[("MAKER_LAMBDA", " ")]);
+
+    assert_eq!(
+        errors(Manifest::load(&source, &Files::Embedded, vars)),
+        vec![r#"agent "Shop", action group "IceCreamMaker": lambda ARN is empty"#]
+    );
+}
+
+#[test]
+fn rejects_duplicated_agent_names() {
+    let agent = manifest(
+        "Shop",
+        "amazon.nova-pro-v1:0",
+        "arn:aws:lambda:us-east-1:123456789012:function:maker",
+    );
+    let agents = agent.trim_start().strip_prefix("agents:\n").unwrap();
+    let source = format!("agents:\n{agents}{agents}");
+
+    assert_eq!(
+        errors(Manifest::load(&source, &Files::Embedded, env(&[]))),
+        vec![r#"agent "Shop" is defined more than once"#]
+    );
+}
+
+#[test]
+fn reports_invalid_agents_and_action_groups() {
+    let source = r#"
+agents:
+  - name: Shop
+    foundationModel: amazon.nova-pro-v1:0
+    instruction: Too short.
+    actionGroups:
+      - name: Waiter
+        executor: returnControl
+      - name: Waiter
+        executor: returnControl
+        functions:
+          - name: StartOrder
+            parameters:
+              quantity:
+                type: float
+"#;
+
+    assert_eq!(
+        errors(Manifest::load(source, &Files::Embedded, env(&[]))),
+        vec![
+            r#"agent "Shop": instruction must be at least 40 characters long"#,
+            r#"agent "Shop", action group "Waiter": expected functions or apiSchema"#,
+            r#"agent "Shop", action group "Waiter": defined more than once"#,
+            r#"agent "Shop", action group "Waiter", function "StartOrder": parameter "quantity" has unsupported type "float", expected one of string, number, integer, boolean, array"#,
+        ]
+    );
+}
+
+#[test]
+fn rejects_manifests_without_agents() {
+    assert_eq!(
+        errors(Manifest::load("agents: []", &Files::Embedded, env(&[]))),
+        vec!["no agents defined"]
+    );
+    assert!(matches!(
+        Manifest::load(
+            "agents:\n  - name: Shop\n    model: nova",
+            &Files::Embedded,
+            env(&[])
+        ),
+        Err(ManifestError::Parse(_))
+    ));
+}
@@ -0,0 +1,187 @@
+use agent_caller::{
+    reply::{ActionGroupInvocation, AgentReply, Citation, Reference},
+    trace::TraceEvent,
+};
+use aws_sdk_bedrockagentruntime::{primitives::Blob, types as bedrock};
+use serde_json::json;
+
+fn chunk(text: &str) -> bedrock::InlineAgentPayloadPart {
+    bedrock::InlineAgentPayloadPart::builder()
+        .bytes(Blob::new(text))
+        .build()
+}
+
+fn cited_chunk(
+    text: &str,
+    location: bedrock::RetrievalResultLocation,
+) -> bedrock::InlineAgentPayloadPart {
+    let citation = bedrock::Citation::builder()
+        .generated_response_part(
+            bedrock::GeneratedResponsePart::builder()
+                .text_response_part(bedrock::TextResponsePart::builder().text(text).build())
+                .build(),
+        )
+        .retrieved_references(
+            bedrock::RetrievedReference::builder()
+                .content(
+                    bedrock::RetrievalResultContent::builder()
+                        .text("Mint Chocolate Chip contains milk and soy.")
+                        .build(),
+                )
+                .location(location)
+                .build(),
+        )
+        .build();
+
+    bedrock::InlineAgentPayloadPart::builder()
+        .bytes(Blob::new(text))
+        .attribution(bedrock::Attribution::builder().citations(citation).build())
+        .build()
+}
+
+fn invocation(action_group: &str, function: &str) -> TraceEvent {
+    TraceEvent::InvocationInput {
+        trace_id: Some("trace-id".to_string()),
+        invocation_type: Some("ACTION_GROUP".to_string()),
+        action_group: Some(ActionGroupInvocation {
+            action_group: action_group.to_string(),
+            function: Some(function.to_string()),
+            api_path: None,
+            http_method: None,
+        }),
+        parameters: Vec::new(),
+        knowledge_base_query: None,
+    }
+}
+
+fn rationale(text: &str) -> TraceEvent {
+    TraceEvent::Rationale {
+        trace_id: Some("trace-id".to_string()),
+        text: Some(text.to_string()),
+    }
+}
+
+#[test]
+fn concatenates_chunks() {
+    let mut reply = AgentReply::new("session-id");
+
+    assert_eq!(
+        reply.push_chunk(chunk("Your Vanilla ")).unwrap(),
+        "Your Vanilla "
+    );
+    assert_eq!(
+        reply.push_chunk(chunk("ice cream is ready.")).unwrap(),
+        "ice cream is ready."
+    );
+    assert_eq!(
+        reply
+            .push_chunk(bedrock::InlineAgentPayloadPart::builder().build())
+            .unwrap(),
+        ""
+    );
+
+    assert_eq!(reply.completion, "Your Vanilla ice cream is ready.");
+    assert_eq!(reply.session_id, "session-id");
+}
+
+#[test]
+fn rejects_chunks_that_are_not_utf8() {
+    let mut reply = AgentReply::new("session-id");
+    let chunk = bedrock::InlineAgentPayloadPart::builder()
+        .bytes(Blob::new(vec![0xff, 0xfe]))
+        .build();
+
+    assert!(reply.push_chunk(chunk).is_err());
+    assert_eq!(reply.completion, "");
+}
+
+#[test]
+fn collects_citations_of_chunks() {
+    let mut reply = AgentReply::new("session-id");
+    let s3 = bedrock::RetrievalResultLocation::builder()
+        .r#type(bedrock::RetrievalResultLocationType::S3)
+        .s3_location(
+            bedrock::RetrievalResultS3Location::builder()
+                .uri("s3://shop/allergens.pdf")
+                .build(),
+        )
+        .build()
+        .unwrap();
+    let web = bedrock::RetrievalResultLocation::builder()
+        .r#type(bedrock::RetrievalResultLocationType::Web)
+        .web_location(
+            bedrock::RetrievalResultWebLocation::builder()
+                .url("https://example.com/allergens")
+                .build(),
+        )
+        .build()
+        .unwrap();
+
+    reply
+        .push_chunk(cited_chunk("It contains milk. ", s3))
+        .unwrap();
+    reply.push_chunk(cited_chunk("And soy.", web)).unwrap();
+
+    let reference = |location: &str| Reference {
+        text: "Mint Chocolate Chip contains milk and soy.".to_string(),
+        location: Some(location.to_string()),
+    };
+    assert_eq!(
+        reply.citations,
+        vec![
+            Citation {
+                text: Some("It contains milk. ".to_string()),
+                references: vec![reference("s3://shop/allergens.pdf")],
+            },
+            Citation {
+                text: Some("And soy.".to_string()),
+                references: vec![reference("https://example.com/allergens")],
+            },
+        ]
+    );
+}
+
+#[test]
+fn records_invoked_action_groups_without_keeping_traces() {
+    let mut reply = AgentReply::new("session-id");
+
+    reply.push_trace(rationale("The client wants a Vanilla ice cream."));
+    reply.push_trace(invocation("IceCreamMaker", "PrepareIceCream"));
+
+    assert_eq!(reply.trace, None);
+    assert_eq!(
+        reply.invoked_action_groups,
+        vec![ActionGroupInvocation {
+            action_group: "IceCreamMaker".to_string(),
+            function: Some("PrepareIceCream".to_string()),
+            api_path: None,
+            http_method: None,
+        }]
+    );
+    assert!(serde_json::to_value(&reply).unwrap().get("trace").is_none());
+}
+
+#[test]
+fn keeps_traces_when_asked_to() {
+    let mut reply = AgentReply::new("session-id").with_trace();
+
+    reply.push_trace(rationale("The client wants a Vanilla ice cream."));
+    reply.push_trace(invocation("IceCreamMaker", "PrepareIceCream"));
+
+    assert_eq!(
+        reply.trace,
+        Some(vec![
+            rationale("The client wants a Vanilla ice cream."),
+            invocation("IceCreamMaker", "PrepareIceCream"),
+        ])
+    );
+    assert_eq!(reply.invoked_action_groups.len(), 1);
+    assert_eq!(
+        serde_json::to_value(&reply).unwrap()["trace"][0],
+        json!({
+            "type": "rationale",
+            "traceId": "trace-id",
+            "text": "The client wants a Vanilla ice cream."
+        })
+    );
+}
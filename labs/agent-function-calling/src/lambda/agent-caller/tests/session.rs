@@ -0,0 +1,48 @@
+use agent_caller::types::ClientPrompt;
+use serde_json::json;
+
+fn prompt(session_id: Option<&str>) -> ClientPrompt {
+    serde_json::from_value(json!({
+        "input": "Hi, my name is Pedro, can I take a Vanilla ice cream?",
+        "sessionId": session_id,
+    }))
+    .expect("valid client prompt")
+}
+
+#[test]
+fn continues_given_session() {
+    let mut prompt = prompt(Some("pedro-session"));
+
+    assert_eq!(prompt.take_session_id(), "pedro-session");
+}
+
+#[test]
+fn starts_new_session_when_none_is_given() {
+    let first = prompt(None).take_session_id();
+    let second = prompt(None).take_session_id();
+
+    assert!(
+        uuid::Uuid::parse_str(&first).is_ok(),
+        "{first} is not a UUID"
+    );
+    assert_ne!(first, second);
+}
+
+#[test]
+fn reads_session_fields_of_prompt() {
+    let prompt: ClientPrompt = serde_json::from_value(json!({
+        "input": "Add a Chocolate one too.",
+        "agent": "IceCreamShop",
+        "sessionId": "pedro-session",
+        "sessionAttributes": { "clientName": "Pedro" },
+        "promptSessionAttributes": { "time": "afternoon" },
+        "includeTrace": true
+    }))
+    .unwrap();
+
+    assert_eq!(prompt.agent.as_deref(), Some("IceCreamShop"));
+    assert_eq!(prompt.session_id.as_deref(), Some("pedro-session"));
+    assert_eq!(prompt.session_attributes["clientName"], "Pedro");
+    assert_eq!(prompt.prompt_session_attributes["time"], "afternoon");
+    assert!(prompt.include_trace);
+}
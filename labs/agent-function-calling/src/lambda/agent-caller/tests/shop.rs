@@ -0,0 +1,267 @@
+use agent_caller::shop::{
+    Session, Shop, ICE_CREAM_MAKER_ACTION_GROUP, PREPARE_ICE_CREAM_FUNCTION, WAITER_ACTION_GROUP,
+};
+use aws_sdk_bedrockagentruntime::types as bedrock;
+use ice_cream_maker::catalog::FlavorCatalog;
+use serde_json::Value;
+use std::collections::HashMap;
+use waiter::repository::InMemoryOrderRepository;
+
+const CATALOG: &str = r#"
+flavors:
+  - name: Vanilla
+    allergens: [milk]
+    stock: 10
+  - name: Strawberry
+    stock: 0
+"#;
+
+fn shop() -> Shop<InMemoryOrderRepository> {
+    Shop::new(
+        InMemoryOrderRepository::new(),
+        FlavorCatalog::from_yaml(CATALOG).expect("valid catalog"),
+    )
+}
+
+async fn try_invoke(
+    shop: &Shop<InMemoryOrderRepository>,
+    input: bedrock::InvocationInputMember,
+) -> Result<bedrock::InvocationResultMember, lambda_runtime::Error> {
+    let attributes = HashMap::new();
+    let session = Session {
+        id: "pedro-session",
+        input_text: "Hi, my name is Pedro, can I take a Vanilla ice cream?",
+        attributes: &attributes,
+        prompt_attributes: &attributes,
+    };
+
+    shop.invoke(&session, &input).await
+}
+
+async fn invoke(
+    shop: &Shop<InMemoryOrderRepository>,
+    input: bedrock::InvocationInputMember,
+) -> bedrock::InvocationResultMember {
+    try_invoke(shop, input)
+        .await
+        .expect("shop runs the invocation")
+}
+
+fn prepare_ice_cream(flavor: &str) -> bedrock::InvocationInputMember {
+    function_call(
+        ICE_CREAM_MAKER_ACTION_GROUP,
+        PREPARE_ICE_CREAM_FUNCTION,
+        flavor,
+    )
+}
+
+fn function_call(
+    action_group: &str,
+    function: &str,
+    flavor: &str,
+) -> bedrock::InvocationInputMember {
+    bedrock::InvocationInputMember::FunctionInvocationInput(
+        bedrock::FunctionInvocationInput::builder()
+            .action_group(action_group)
+            .function(function)
+            .parameters(
+                bedrock::FunctionParameter::builder()
+                    .name("flavor")
+                    .r#type("string")
+                    .value(flavor)
+                    .build(),
+            )
+            .build()
+            .unwrap(),
+    )
+}
+
+fn api_call(
+    http_method: &str,
+    api_path: &str,
+    parameters: &[(&str, &str)],
+    body: &[(&str, &str)],
+) -> bedrock::InvocationInputMember {
+    let mut input = bedrock::ApiInvocationInput::builder()
+        .action_group(WAITER_ACTION_GROUP)
+        .http_method(http_method)
+        .api_path(api_path);
+
+    for (name, value) in parameters {
+        input = input.parameters(
+            bedrock::ApiParameter::builder()
+                .name(*name)
+                .r#type("string")
+                .value(*value)
+                .build(),
+        );
+    }
+    if !body.is_empty() {
+        let properties = body
+            .iter()
+            .map(|(name, value)| {
+                bedrock::Parameter::builder()
+                    .name(*name)
+                    .r#type("string")
+                    .value(*value)
+                    .build()
+            })
+            .collect();
+        input = input.request_body(
+            bedrock::ApiRequestBody::builder()
+                .content(
+                    "application/json",
+                    bedrock::PropertyParameters::builder()
+                        .set_properties(Some(properties))
+                        .build(),
+                )
+                .build(),
+        );
+    }
+
+    bedrock::InvocationInputMember::ApiInvocationInput(input.build().unwrap())
+}
+
+fn function_result(
+    result: &bedrock::InvocationResultMember,
+) -> (Option<&bedrock::ResponseState>, &str) {
+    let bedrock::InvocationResultMember::FunctionResult(result) = result else {
+        panic!("expected a function result, got {result:?}");
+    };
+
+    (
+        result.response_state(),
+        result.response_body().unwrap()["TEXT"].body().unwrap(),
+    )
+}
+
+fn api_result(result: &bedrock::InvocationResultMember) -> (i32, Value) {
+    let bedrock::InvocationResultMember::ApiResult(result) = result else {
+        panic!("expected an API result, got {result:?}");
+    };
+    let body = result.response_body().unwrap()["application/json"]
+        .body()
+        .unwrap();
+
+    (
+        result.http_status_code().unwrap(),
+        serde_json::from_str(body).unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn runs_functions_with_the_ice_cream_maker() {
+    let shop = shop();
+
+    let result = invoke(&shop, prepare_ice_cream("vanilla")).await;
+
+    let bedrock::InvocationResultMember::FunctionResult(function) = &result else {
+        panic!("expected a function result, got {result:?}");
+    };
+    assert_eq!(function.action_group(), "IceCreamMaker");
+    assert_eq!(function.function(), Some("PrepareIceCream"));
+    assert_eq!(
+        function_result(&result),
+        (None, "Ice cream of Vanilla was made. It contains: milk.")
+    );
+}
+
+#[tokio::test]
+async fn reprompts_flavors_out_of_the_catalog() {
+    let shop = shop();
+
+    let result = invoke(&shop, prepare_ice_cream("Strawberry")).await;
+
+    assert_eq!(
+        function_result(&result),
+        (
+            Some(&bedrock::ResponseState::Reprompt),
+            "Strawberry is out of stock. Available flavors are: Vanilla."
+        )
+    );
+}
+
+#[tokio::test]
+async fn routes_api_calls_to_the_waiter() {
+    let shop = shop();
+
+    let started = invoke(
+        &shop,
+        api_call("POST", "/start-order", &[], &[("client_name", "Pedro")]),
+    )
+    .await;
+    let (status, body) = api_result(&started);
+    assert_eq!(status, 201);
+    let order_id = body["orderId"].as_str().expect("order id").to_string();
+
+    let added = invoke(
+        &shop,
+        api_call(
+            "POST",
+            "/add-ice-cream/{orderId}",
+            &[("orderId", &order_id)],
+            &[("flavor", "Vanilla")],
+        ),
+    )
+    .await;
+
+    let bedrock::InvocationResultMember::ApiResult(api) = &added else {
+        panic!("expected an API result, got {added:?}");
+    };
+    assert_eq!(api.action_group(), "Waiter");
+    assert_eq!(api.api_path(), Some("/add-ice-cream/{orderId}"));
+    assert_eq!(api.http_method(), Some("POST"));
+    assert_eq!(api_result(&added).0, 201);
+}
+
+#[tokio::test]
+async fn reports_unknown_api_paths_to_the_agent() {
+    let shop = shop();
+
+    let result = invoke(&shop, api_call("GET", "/menu", &[], &[])).await;
+
+    let (status, body) = api_result(&result);
+    assert_eq!(status, 404);
+    assert_eq!(body["errorCode"], "ROUTE_NOT_FOUND");
+}
+
+#[tokio::test]
+async fn rejects_functions_it_cannot_run() {
+    let shop = shop();
+
+    for (action_group, function) in [
+        (ICE_CREAM_MAKER_ACTION_GROUP, "CleanMachine"),
+        ("Kitchen", PREPARE_ICE_CREAM_FUNCTION),
+    ] {
+        let error = try_invoke(&shop, function_call(action_group, function, "Vanilla"))
+            .await
+            .expect_err("unknown function is rejected");
+
+        assert!(
+            error
+                .to_string()
+                .contains(&format!("{function:?} of action group {action_group:?}")),
+            "{error}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn rejects_api_calls_of_other_action_groups() {
+    let shop = shop();
+    let input = bedrock::ApiInvocationInput::builder()
+        .action_group("Kitchen")
+        .http_method("POST")
+        .api_path("/start-order")
+        .build()
+        .unwrap();
+
+    let error = try_invoke(
+        &shop,
+        bedrock::InvocationInputMember::ApiInvocationInput(input),
+    )
+    .await
+    .expect_err("unknown action group is rejected");
+
+    assert!(error.to_string().contains("\"Kitchen\""), "{error}");
+}
@@ -0,0 +1,58 @@
+use agent_caller::{
+    reply::AgentReply,
+    stream::{sse, StreamEvent},
+};
+
+#[test]
+fn frames_events_with_their_name_and_data() {
+    assert_eq!(
+        sse("chunk", r#"{"text":"Hi"}"#),
+        "event: chunk\ndata: {\"text\":\"Hi\"}\n\n"
+    );
+}
+
+#[test]
+fn frames_every_line_of_multi_line_data() {
+    assert_eq!(
+        sse("message", "first\nsecond\r\nthird\rfourth"),
+        "event: message\ndata: first\ndata: second\ndata: third\ndata: fourth\n\n"
+    );
+    assert_eq!(
+        sse("message", "trailing\n"),
+        "event: message\ndata: trailing\ndata: \n\n"
+    );
+    assert_eq!(sse("message", ""), "event: message\ndata: \n\n");
+}
+
+#[test]
+fn keeps_line_breaks_of_chunks_inside_their_json() {
+    let event = StreamEvent::Chunk {
+        text: "Your order:\n- Vanilla\n",
+    };
+
+    assert_eq!(
+        event.to_sse().unwrap(),
+        "event: chunk\ndata: {\"text\":\"Your order:\\n- Vanilla\\n\"}\n\n"
+    );
+}
+
+#[test]
+fn names_every_event() {
+    let reply = AgentReply::new("session-id");
+    let events = [
+        StreamEvent::Reply(&reply),
+        StreamEvent::Error {
+            message: "The agent couldn't answer.".to_string(),
+        },
+    ];
+
+    let frames: Vec<String> = events.iter().map(|event| event.to_sse().unwrap()).collect();
+
+    assert_eq!(
+        frames,
+        vec![
+            "event: reply\ndata: {\"sessionId\":\"session-id\",\"completion\":\"\",\"invokedActionGroups\":[],\"citations\":[]}\n\n",
+            "event: error\ndata: {\"message\":\"The agent couldn't answer.\"}\n\n",
+        ]
+    );
+}
@@ -0,0 +1,304 @@
+use agent_caller::{
+    reply::ActionGroupInvocation,
+    trace::{Step, TraceEvent, TraceParameter},
+};
+use aws_sdk_bedrockagentruntime::types as bedrock;
+use serde_json::json;
+
+fn orchestration(trace: bedrock::OrchestrationTrace) -> TraceEvent {
+    TraceEvent::from(&bedrock::Trace::OrchestrationTrace(trace))
+}
+
+#[test]
+fn converts_rationale() {
+    let event = orchestration(bedrock::OrchestrationTrace::Rationale(
+        bedrock::Rationale::builder()
+            .trace_id("trace-1")
+            .text("The client wants a Vanilla ice cream.")
+            .build(),
+    ));
+
+    assert_eq!(
+        event,
+        TraceEvent::Rationale {
+            trace_id: Some("trace-1".to_string()),
+            text: Some("The client wants a Vanilla ice cream.".to_string()),
+        }
+    );
+    assert_eq!(event.action_group(), None);
+}
+
+#[test]
+fn converts_action_group_invocation() {
+    let event = orchestration(bedrock::OrchestrationTrace::InvocationInput(
+        bedrock::InvocationInput::builder()
+            .trace_id("trace-2")
+            .invocation_type(bedrock::InvocationType::ActionGroup)
+            .action_group_invocation_input(
+                bedrock::ActionGroupInvocationInput::builder()
+                    .action_group_name("IceCreamMaker")
+                    .function("PrepareIceCream")
+                    .parameters(
+                        bedrock::Parameter::builder()
+                            .name("flavor")
+                            .r#type("string")
+                            .value("Vanilla")
+                            .build(),
+                    )
+                    .build(),
+            )
+            .build(),
+    ));
+
+    let action_group = ActionGroupInvocation {
+        action_group: "IceCreamMaker".to_string(),
+        function: Some("PrepareIceCream".to_string()),
+        api_path: None,
+        http_method: None,
+    };
+    assert_eq!(
+        event,
+        TraceEvent::InvocationInput {
+            trace_id: Some("trace-2".to_string()),
+            invocation_type: Some("ACTION_GROUP".to_string()),
+            action_group: Some(action_group.clone()),
+            parameters: vec![TraceParameter {
+                name: Some("flavor".to_string()),
+                r#type: Some("string".to_string()),
+                value: Some("Vanilla".to_string()),
+            }],
+            knowledge_base_query: None,
+        }
+    );
+    assert_eq!(event.action_group(), Some(&action_group));
+}
+
+#[test]
+fn converts_api_invocation() {
+    let event = orchestration(bedrock::OrchestrationTrace::InvocationInput(
+        bedrock::InvocationInput::builder()
+            .invocation_type(bedrock::InvocationType::ActionGroup)
+            .action_group_invocation_input(
+                bedrock::ActionGroupInvocationInput::builder()
+                    .action_group_name("Waiter")
+                    .api_path("/start-order")
+                    .verb("POST")
+                    .build(),
+            )
+            .build(),
+    ));
+
+    assert_eq!(
+        event.action_group(),
+        Some(&ActionGroupInvocation {
+            action_group: "Waiter".to_string(),
+            function: None,
+            api_path: Some("/start-order".to_string()),
+            http_method: Some("POST".to_string()),
+        })
+    );
+}
+
+#[test]
+fn converts_observation() {
+    let event = orchestration(bedrock::OrchestrationTrace::Observation(
+        bedrock::Observation::builder()
+            .trace_id("trace-3")
+            .r#type(bedrock::Type::ActionGroup)
+            .action_group_invocation_output(
+                bedrock::ActionGroupInvocationOutput::builder()
+                    .text("Ice cream of Vanilla was made.")
+                    .build(),
+            )
+            .build(),
+    ));
+
+    assert_eq!(
+        event,
+        TraceEvent::Observation {
+            trace_id: Some("trace-3".to_string()),
+            observation_type: Some("ACTION_GROUP".to_string()),
+            action_group_output: Some("Ice cream of Vanilla was made.".to_string()),
+            retrieved_references: 0,
+            final_response: None,
+            reprompt_response: None,
+        }
+    );
+}
+
+#[test]
+fn converts_model_invocations_of_each_step() {
+    let input = bedrock::ModelInvocationInput::builder()
+        .trace_id("trace-4")
+        .r#type(bedrock::PromptType::PreProcessing)
+        .text("Classify the input.")
+        .build();
+    let event = TraceEvent::from(&bedrock::Trace::PreProcessingTrace(
+        bedrock::PreProcessingTrace::ModelInvocationInput(input),
+    ));
+
+    assert_eq!(
+        event,
+        TraceEvent::ModelInvocationInput {
+            step: Step::PreProcessing,
+            trace_id: Some("trace-4".to_string()),
+            prompt_type: Some("PRE_PROCESSING".to_string()),
+            text: Some("Classify the input.".to_string()),
+        }
+    );
+
+    let output = bedrock::PreProcessingModelInvocationOutput::builder()
+        .trace_id("trace-5")
+        .raw_response(
+            bedrock::RawResponse::builder()
+                .content("<category>D</category>")
+                .build(),
+        )
+        .parsed_response(
+            bedrock::PreProcessingParsedResponse::builder()
+                .is_valid(true)
+                .rationale("It is an order.")
+                .build(),
+        )
+        .build();
+    let event = TraceEvent::from(&bedrock::Trace::PreProcessingTrace(
+        bedrock::PreProcessingTrace::ModelInvocationOutput(output),
+    ));
+
+    assert_eq!(
+        event,
+        TraceEvent::ModelInvocationOutput {
+            step: Step::PreProcessing,
+            trace_id: Some("trace-5".to_string()),
+            raw_response: Some("<category>D</category>".to_string()),
+            parsed_response: None,
+            is_valid: Some(true),
+            rationale: Some("It is an order.".to_string()),
+        }
+    );
+
+    let event = orchestration(bedrock::OrchestrationTrace::ModelInvocationInput(
+        bedrock::ModelInvocationInput::builder()
+            .r#type(bedrock::PromptType::Orchestration)
+            .build(),
+    ));
+    assert!(matches!(
+        event,
+        TraceEvent::ModelInvocationInput {
+            step: Step::Orchestration,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn converts_guardrail_and_failure() {
+    let guardrail = TraceEvent::from(&bedrock::Trace::GuardrailTrace(
+        bedrock::GuardrailTrace::builder()
+            .trace_id("trace-6")
+            .action(bedrock::GuardrailAction::Intervened)
+            .build(),
+    ));
+    let failure = TraceEvent::from(&bedrock::Trace::FailureTrace(
+        bedrock::FailureTrace::builder()
+            .trace_id("trace-7")
+            .failure_reason("Lambda function timed out.")
+            .build(),
+    ));
+
+    assert_eq!(
+        guardrail,
+        TraceEvent::Guardrail {
+            trace_id: Some("trace-6".to_string()),
+            action: Some("INTERVENED".to_string()),
+        }
+    );
+    assert_eq!(
+        failure,
+        TraceEvent::Failure {
+            trace_id: Some("trace-7".to_string()),
+            reason: Some("Lambda function timed out.".to_string()),
+        }
+    );
+}
+
+#[test]
+fn converts_routing_classifier() {
+    let event = TraceEvent::from(&bedrock::Trace::RoutingClassifierTrace(
+        bedrock::RoutingClassifierTrace::ModelInvocationOutput(
+            bedrock::RoutingClassifierModelInvocationOutput::builder()
+                .trace_id("trace-9")
+                .raw_response(bedrock::RawResponse::builder().content("Waiter").build())
+                .build(),
+        ),
+    ));
+
+    assert_eq!(
+        event,
+        TraceEvent::ModelInvocationOutput {
+            step: Step::RoutingClassifier,
+            trace_id: Some("trace-9".to_string()),
+            raw_response: Some("Waiter".to_string()),
+            parsed_response: None,
+            is_valid: None,
+            rationale: None,
+        }
+    );
+
+    let event = TraceEvent::from(&bedrock::Trace::RoutingClassifierTrace(
+        bedrock::RoutingClassifierTrace::Observation(
+            bedrock::Observation::builder()
+                .trace_id("trace-10")
+                .r#type(bedrock::Type::Finish)
+                .build(),
+        ),
+    ));
+    assert!(matches!(
+        event,
+        TraceEvent::Observation { observation_type: Some(observation_type), .. }
+            if observation_type == "FINISH"
+    ));
+}
+
+#[test]
+fn converts_custom_orchestration() {
+    let event = TraceEvent::from(&bedrock::Trace::CustomOrchestrationTrace(
+        bedrock::CustomOrchestrationTrace::builder()
+            .trace_id("trace-11")
+            .event(
+                bedrock::CustomOrchestrationTraceEvent::builder()
+                    .text("Calling the model.")
+                    .build(),
+            )
+            .build(),
+    ));
+
+    assert_eq!(
+        event,
+        TraceEvent::CustomOrchestration {
+            trace_id: Some("trace-11".to_string()),
+            text: Some("Calling the model.".to_string()),
+        }
+    );
+}
+
+#[test]
+fn serializes_with_type_tag_and_camel_case_fields() {
+    let event = TraceEvent::ModelInvocationInput {
+        step: Step::PostProcessing,
+        trace_id: Some("trace-8".to_string()),
+        prompt_type: Some("POST_PROCESSING".to_string()),
+        text: None,
+    };
+
+    assert_eq!(
+        serde_json::to_value(&event).unwrap(),
+        json!({
+            "type": "modelInvocationInput",
+            "step": "postProcessing",
+            "traceId": "trace-8",
+            "promptType": "POST_PROCESSING",
+            "text": null
+        })
+    );
+}
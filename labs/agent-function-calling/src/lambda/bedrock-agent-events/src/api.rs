@@ -0,0 +1,120 @@
+/**
+ * Events for action groups defined with an OpenAPI schema.
+ *
+ * Reference:
+ * - https://docs.aws.amazon.com/bedrock/latest/userguide/agents-lambda.html
+ */
+use crate::{value, Agent, BodyError, ParameterType, Parameters};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::HashMap;
+
+//------------------- Request
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentApiRequest {
+    pub message_version: String,
+    pub agent: Agent,
+    pub input_text: String,
+    pub session_id: String,
+    pub action_group: String,
+    pub api_path: String,
+    pub http_method: String,
+    #[serde(default)]
+    pub parameters: Vec<Parameters>,
+    #[serde(default)]
+    pub session_attributes: HashMap<String, String>,
+    #[serde(default)]
+    pub prompt_session_attributes: HashMap<String, String>,
+    /// Not sent by the agent when the operation doesn't define a request body.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<RequestBody>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestBody {
+    pub content: HashMap<String, RequestBodyContentType>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct RequestBodyContentType {
+    pub properties: Vec<Property>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Property {
+    pub name: String,
+    pub r#type: String,
+    pub value: String,
+}
+
+impl AgentApiRequest {
+    /// Reads the request body of the given content type, e.g. `application/json`, into `T`.
+    ///
+    /// Property values are converted according to their declared type before deserializing,
+    /// so `integer`, `number`, `boolean` and `array` properties can be read into fields of those types.
+    pub fn body<T: DeserializeOwned>(&self, content_type: &str) -> Result<T, BodyError> {
+        let content = self
+            .request_body
+            .as_ref()
+            .and_then(|request_body| request_body.content.get(content_type))
+            .ok_or_else(|| BodyError::MissingContent {
+                content_type: content_type.to_string(),
+            })?;
+
+        let properties = content
+            .properties
+            .iter()
+            .map(|property| {
+                let r#type = ParameterType::from(property.r#type.clone());
+                let value = value::coerce(&r#type, &property.value).ok_or_else(|| {
+                    BodyError::InvalidProperty {
+                        name: property.name.clone(),
+                        r#type: property.r#type.clone(),
+                        value: property.value.clone(),
+                    }
+                })?;
+
+                Ok((property.name.clone(), value))
+            })
+            .collect::<Result<serde_json::Map<_, _>, BodyError>>()?;
+
+        serde_json::from_value(serde_json::Value::Object(properties))
+            .map_err(|error| BodyError::Mismatch(error.to_string()))
+    }
+}
+
+//------------------- Response
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentApiResponse {
+    pub message_version: String,
+    pub response: ApiResponse,
+    pub session_attributes: HashMap<String, String>,
+    pub prompt_session_attributes: HashMap<String, String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiResponse {
+    pub action_group: String,
+    pub api_path: String,
+    pub http_method: String,
+    pub http_status_code: i32,
+    pub response_body: ResponseBody,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseBody {
+    #[serde(flatten)]
+    pub content: HashMap<String, ResponseBodyContentType>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseBodyContentType {
+    pub body: String, // JSON-formatted string
+}
@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+
+/// Agent that invoked the action group.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Agent {
+    pub name: String,
+    pub id: String,
+    pub alias: String,
+    pub version: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Parameters {
+    pub name: String,
+    pub r#type: ParameterType,
+    pub value: String,
+}
+
+/// Types supported for parameters, see:
+/// https://docs.aws.amazon.com/bedrock/latest/APIReference/API_agent_ParameterDetail.html
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParameterType {
+    Text,
+    Number,
+    Integer,
+    Boolean,
+    Array,
+    Unknown(String),
+}
+
+impl ParameterType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            ParameterType::Text => "string",
+            ParameterType::Number => "number",
+            ParameterType::Integer => "integer",
+            ParameterType::Boolean => "boolean",
+            ParameterType::Array => "array",
+            ParameterType::Unknown(unknown) => unknown.as_str(),
+        }
+    }
+}
+
+impl Serialize for ParameterType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ParameterType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+
+        Ok(ParameterType::from(s))
+    }
+}
+
+impl From<String> for ParameterType {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "string" => ParameterType::Text,
+            "number" => ParameterType::Number,
+            "integer" => ParameterType::Integer,
+            "boolean" => ParameterType::Boolean,
+            "array" => ParameterType::Array,
+            unknown => ParameterType::Unknown(unknown.to_owned()),
+        }
+    }
+}
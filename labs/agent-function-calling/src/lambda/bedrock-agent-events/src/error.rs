@@ -0,0 +1,77 @@
+use std::fmt;
+
+/// Reasons why the request body couldn't be read into the expected type.
+///
+/// Messages are meant to be sent back to the agent, so it can fix the request.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BodyError {
+    /// The request has no body for the given content type.
+    MissingContent { content_type: String },
+    /// A property value doesn't hold its declared type.
+    InvalidProperty {
+        name: String,
+        r#type: String,
+        value: String,
+    },
+    /// The properties don't match the expected type, e.g. a required one is missing.
+    Mismatch(String),
+}
+
+impl fmt::Display for BodyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BodyError::MissingContent { content_type } => {
+                write!(f, "Expected a request body with {content_type} content.")
+            }
+            BodyError::InvalidProperty {
+                name,
+                r#type,
+                value,
+            } => write!(
+                f,
+                "Property {name:?} is declared as {type} but its value {value:?} is not a valid {type}."
+            ),
+            BodyError::Mismatch(reason) => write!(f, "Invalid request body: {reason}."),
+        }
+    }
+}
+
+impl std::error::Error for BodyError {}
+
+/// Reasons why a parameter of a function request couldn't be read into the expected type.
+///
+/// Messages name the parameter, so they can be sent back to the agent to fix the request.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParameterError {
+    /// A required parameter wasn't sent.
+    Missing { name: String },
+    /// The parameter value doesn't hold its declared type.
+    Invalid {
+        name: String,
+        r#type: String,
+        value: String,
+    },
+    /// The parameter holds its declared type, but it can't be read into the expected one.
+    Mismatch { name: String, reason: String },
+}
+
+impl fmt::Display for ParameterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParameterError::Missing { name } => write!(f, "Missing required parameter {name:?}."),
+            ParameterError::Invalid {
+                name,
+                r#type,
+                value,
+            } => write!(
+                f,
+                "Parameter {name:?} is declared as {type} but its value {value:?} is not a valid {type}."
+            ),
+            ParameterError::Mismatch { name, reason } => {
+                write!(f, "Invalid parameter {name:?}: {reason}.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParameterError {}
@@ -0,0 +1,137 @@
+/**
+ * Events for action groups defined with a function details schema.
+ *
+ * Reference:
+ * - https://docs.aws.amazon.com/bedrock/latest/userguide/agents-lambda.html
+ */
+use crate::{value, Agent, ParameterError, Parameters};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::HashMap;
+
+//------------------- Request
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentRequest {
+    pub message_version: String,
+    pub agent: Agent,
+    pub input_text: String,
+    pub session_id: String,
+    pub action_group: String,
+    pub function: String,
+    #[serde(default)]
+    pub parameters: Vec<Parameters>,
+    #[serde(default)]
+    pub session_attributes: HashMap<String, String>,
+    #[serde(default)]
+    pub prompt_session_attributes: HashMap<String, String>,
+}
+
+impl AgentRequest {
+    /// Reads the required parameter `name` into `T`.
+    ///
+    /// The value is converted according to its declared type before deserializing,
+    /// so `integer`, `number`, `boolean` and `array` parameters can be read into those types.
+    pub fn param<T: DeserializeOwned>(&self, name: &str) -> Result<T, ParameterError> {
+        self.optional_param(name)?
+            .ok_or_else(|| ParameterError::Missing {
+                name: name.to_string(),
+            })
+    }
+
+    /// Reads the parameter `name` into `T`, `None` if the agent didn't send it.
+    pub fn optional_param<T: DeserializeOwned>(
+        &self,
+        name: &str,
+    ) -> Result<Option<T>, ParameterError> {
+        let Some(parameter) = self
+            .parameters
+            .iter()
+            .find(|parameter| parameter.name == name)
+        else {
+            return Ok(None);
+        };
+
+        let value = value::coerce(&parameter.r#type, &parameter.value).ok_or_else(|| {
+            ParameterError::Invalid {
+                name: name.to_string(),
+                r#type: parameter.r#type.as_str().to_string(),
+                value: parameter.value.clone(),
+            }
+        })?;
+
+        serde_json::from_value(value)
+            .map(Some)
+            .map_err(|error| ParameterError::Mismatch {
+                name: name.to_string(),
+                reason: error.to_string(),
+            })
+    }
+}
+
+//------------------- Response
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentResponse {
+    pub message_version: String,
+    pub response: Response,
+    pub session_attributes: HashMap<String, String>,
+    pub prompt_session_attributes: HashMap<String, String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Response {
+    pub action_group: String,
+    pub function: String,
+    pub function_response: FunctionResponse,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionResponse {
+    #[serde(flatten)]
+    pub response_type: ResponseType,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum ResponseType {
+    /// Signals the agent that the function failed or that it should ask the user again,
+    /// optionally explaining why in the body.
+    State {
+        #[serde(rename = "responseState")]
+        response_state: ResponseState,
+        #[serde(
+            rename = "responseBody",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        response_body: Option<ResponseBody>,
+    },
+    Body {
+        #[serde(rename = "responseBody")]
+        response_body: ResponseBody,
+    },
+}
+
+/// Sent as `"FAILURE"` or `"REPROMPT"`.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ResponseState {
+    Failure,
+    Reprompt,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseBody {
+    #[serde(flatten)]
+    pub content: HashMap<String, ContentType>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ContentType {
+    pub body: String, // JSON-formatted string
+}
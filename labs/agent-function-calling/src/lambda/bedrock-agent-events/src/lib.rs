@@ -0,0 +1,21 @@
+/**
+ * Events exchanged between AWS Bedrock agents and the lambda functions of their action groups.
+ *
+ * An action group is defined either with an OpenAPI schema, see [`api`], or with a
+ * function details schema, see [`function`].
+ *
+ * Reference:
+ * - https://docs.aws.amazon.com/bedrock/latest/userguide/agents-lambda.html
+ */
+pub mod api;
+pub mod function;
+
+mod common;
+mod error;
+mod value;
+
+pub use common::{Agent, ParameterType, Parameters};
+pub use error::{BodyError, ParameterError};
+
+/// Version of the message format sent back to the agent.
+pub const MESSAGE_VERSION: &str = "1.0";
@@ -0,0 +1,60 @@
+use crate::ParameterType;
+use serde_json::Value;
+
+/// Converts the raw string value sent by the agent into JSON according to its declared type.
+///
+/// Returns `None` when the value doesn't hold the declared type.
+pub(crate) fn coerce(r#type: &ParameterType, value: &str) -> Option<Value> {
+    match r#type {
+        ParameterType::Text => Some(Value::String(value.to_string())),
+        ParameterType::Integer => value.trim().parse::<i64>().ok().map(Value::from),
+        ParameterType::Number => coerce_number(value.trim()),
+        ParameterType::Boolean => match value.trim().to_ascii_lowercase().as_str() {
+            "true" => Some(Value::Bool(true)),
+            "false" => Some(Value::Bool(false)),
+            _ => None,
+        },
+        ParameterType::Array => coerce_array(value.trim()),
+        // Types not listed by Bedrock, e.g. objects, are passed through when they hold JSON.
+        ParameterType::Unknown(_) => {
+            Some(serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_string())))
+        }
+    }
+}
+
+/// Integers are kept as such, so they can also be read into integer fields.
+fn coerce_number(value: &str) -> Option<Value> {
+    if let Ok(integer) = value.parse::<i64>() {
+        return Some(Value::from(integer));
+    }
+
+    value
+        .parse::<f64>()
+        .ok()
+        .and_then(serde_json::Number::from_f64)
+        .map(Value::Number)
+}
+
+/// Arrays arrive either as JSON, e.g. `["Vanilla","Chocolate"]`,
+/// or as a plain list, e.g. `[Vanilla, Chocolate]`.
+fn coerce_array(value: &str) -> Option<Value> {
+    if let Ok(array @ Value::Array(_)) = serde_json::from_str(value) {
+        return Some(array);
+    }
+
+    let items = value.strip_prefix('[')?.strip_suffix(']')?.trim();
+    if items.is_empty() {
+        return Some(Value::Array(Vec::new()));
+    }
+
+    let items = items
+        .split(',')
+        .map(|item| {
+            let item = item.trim().trim_matches(|c| c == '"' || c == '\'');
+
+            serde_json::from_str(item).unwrap_or_else(|_| Value::String(item.to_string()))
+        })
+        .collect();
+
+    Some(Value::Array(items))
+}
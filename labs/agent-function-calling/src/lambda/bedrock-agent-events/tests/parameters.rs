@@ -0,0 +1,78 @@
+use bedrock_agent_events::{function::AgentRequest, ParameterError};
+use serde_json::{json, Value};
+
+fn request_with_parameters(parameters: Value) -> AgentRequest {
+    let mut request: Value =
+        serde_json::from_str(include_str!("fixtures/function-request.json")).unwrap();
+    request["parameters"] = parameters;
+
+    serde_json::from_value(request).unwrap()
+}
+
+#[test]
+fn looks_up_parameters_by_name() {
+    let request = request_with_parameters(json!([
+        { "name": "scoops", "type": "integer", "value": "2" },
+        { "name": "flavor", "type": "string", "value": "Vanilla" },
+        { "name": "with_topping", "type": "boolean", "value": "true" },
+        { "name": "extra_flavors", "type": "array", "value": "[Chocolate, Cookie Dough]" }
+    ]));
+
+    assert_eq!(request.param::<String>("flavor").unwrap(), "Vanilla");
+    assert_eq!(request.param::<u32>("scoops").unwrap(), 2);
+    assert!(request.param::<bool>("with_topping").unwrap());
+    assert_eq!(
+        request.param::<Vec<String>>("extra_flavors").unwrap(),
+        ["Chocolate", "Cookie Dough"]
+    );
+}
+
+#[test]
+fn optional_parameters_may_be_missing() {
+    let request = request_with_parameters(json!([
+        { "name": "flavor", "type": "string", "value": "Vanilla" }
+    ]));
+
+    assert_eq!(request.optional_param::<u32>("scoops").unwrap(), None);
+    assert_eq!(
+        request
+            .optional_param::<String>("flavor")
+            .unwrap()
+            .as_deref(),
+        Some("Vanilla")
+    );
+}
+
+#[test]
+fn names_missing_required_parameters() {
+    let request = request_with_parameters(json!([]));
+
+    let error = request.param::<String>("flavor").unwrap_err();
+    assert_eq!(
+        error,
+        ParameterError::Missing {
+            name: "flavor".to_string()
+        }
+    );
+    assert_eq!(error.to_string(), r#"Missing required parameter "flavor"."#);
+}
+
+#[test]
+fn names_malformed_parameters() {
+    let request = request_with_parameters(json!([
+        { "name": "scoops", "type": "integer", "value": "two" },
+        { "name": "flavor", "type": "string", "value": "Vanilla" }
+    ]));
+
+    let error = request.param::<u32>("scoops").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        r#"Parameter "scoops" is declared as integer but its value "two" is not a valid integer."#
+    );
+
+    let error = request.param::<u32>("flavor").unwrap_err();
+    assert!(matches!(error, ParameterError::Mismatch { ref name, .. } if name == "flavor"));
+    assert!(error
+        .to_string()
+        .starts_with(r#"Invalid parameter "flavor": "#));
+}
@@ -0,0 +1,126 @@
+use bedrock_agent_events::{api::AgentApiRequest, BodyError};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+fn request_with_properties(properties: Value) -> AgentApiRequest {
+    let mut request: Value =
+        serde_json::from_str(include_str!("fixtures/api-request.json")).unwrap();
+    request["requestBody"]["content"]["application/json"]["properties"] = properties;
+
+    serde_json::from_value(request).unwrap()
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Order {
+    flavor: String,
+    scoops: u32,
+    price: f64,
+    with_topping: bool,
+    extra_flavors: Vec<String>,
+    note: Option<String>,
+}
+
+#[test]
+fn coerces_properties_per_declared_type() {
+    let request = request_with_properties(json!([
+        { "name": "flavor", "type": "string", "value": "Vanilla" },
+        { "name": "scoops", "type": "integer", "value": "2" },
+        { "name": "price", "type": "number", "value": "3.5" },
+        { "name": "with_topping", "type": "boolean", "value": "True" },
+        { "name": "extra_flavors", "type": "array", "value": "[Chocolate, Cookie Dough]" }
+    ]));
+
+    let order: Order = request.body("application/json").unwrap();
+
+    assert_eq!(
+        order,
+        Order {
+            flavor: "Vanilla".to_string(),
+            scoops: 2,
+            price: 3.5,
+            with_topping: true,
+            extra_flavors: vec!["Chocolate".to_string(), "Cookie Dough".to_string()],
+            note: None,
+        }
+    );
+}
+
+#[test]
+fn coerces_json_arrays() {
+    #[derive(Deserialize)]
+    struct Scoops {
+        scoops: Vec<u32>,
+    }
+
+    let request = request_with_properties(json!([
+        { "name": "scoops", "type": "array", "value": "[1, 2, 3]" }
+    ]));
+
+    let body: Scoops = request.body("application/json").unwrap();
+
+    assert_eq!(body.scoops, vec![1, 2, 3]);
+}
+
+#[test]
+fn integer_numbers_fit_integer_fields() {
+    #[derive(Deserialize)]
+    struct Quantity {
+        quantity: u32,
+    }
+
+    let request = request_with_properties(json!([
+        { "name": "quantity", "type": "number", "value": "4" }
+    ]));
+
+    let body: Quantity = request.body("application/json").unwrap();
+
+    assert_eq!(body.quantity, 4);
+}
+
+#[test]
+fn reports_values_not_holding_their_type() {
+    let request = request_with_properties(json!([
+        { "name": "scoops", "type": "integer", "value": "two" }
+    ]));
+
+    let error = request.body::<Order>("application/json").unwrap_err();
+
+    assert_eq!(
+        error,
+        BodyError::InvalidProperty {
+            name: "scoops".to_string(),
+            r#type: "integer".to_string(),
+            value: "two".to_string(),
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        r#"Property "scoops" is declared as integer but its value "two" is not a valid integer."#
+    );
+}
+
+#[test]
+fn reports_missing_properties() {
+    let request = request_with_properties(json!([
+        { "name": "flavor", "type": "string", "value": "Vanilla" }
+    ]));
+
+    let error = request.body::<Order>("application/json").unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "Invalid request body: missing field `scoops`."
+    );
+}
+
+#[test]
+fn reports_missing_content() {
+    let request = request_with_properties(json!([]));
+
+    let error = request.body::<Value>("text/plain").unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "Expected a request body with text/plain content."
+    );
+}
@@ -0,0 +1,126 @@
+use bedrock_agent_events::{
+    api::{AgentApiRequest, AgentApiResponse},
+    function::{AgentRequest, AgentResponse, ResponseState, ResponseType},
+    ParameterType,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+/// Deserializes the fixture into `T`, serializes it back and checks nothing was lost on the way.
+fn assert_round_trip<T>(fixture: &str) -> T
+where
+    T: DeserializeOwned + Serialize,
+{
+    let expected: Value = serde_json::from_str(fixture).expect("fixture is valid JSON");
+    let event: T = serde_json::from_str(fixture).expect("fixture deserializes");
+    let actual = serde_json::to_value(&event).expect("event serializes");
+
+    assert_eq!(actual, expected);
+
+    event
+}
+
+#[test]
+fn api_request_round_trip() {
+    let request: AgentApiRequest = assert_round_trip(include_str!("fixtures/api-request.json"));
+
+    assert_eq!(request.api_path, "/add-ice-cream/{orderId}");
+    assert_eq!(request.http_method, "POST");
+    assert_eq!(request.parameters[0].name, "orderId");
+    assert_eq!(request.parameters[0].r#type, ParameterType::Text);
+
+    let body = request.request_body.expect("request body is present");
+    let properties = &body.content["application/json"].properties;
+    assert_eq!(properties[0].name, "flavor");
+    assert_eq!(properties[0].value, "Vanilla");
+}
+
+#[test]
+fn api_request_without_body() {
+    let mut fixture: Value =
+        serde_json::from_str(include_str!("fixtures/api-request.json")).unwrap();
+    fixture.as_object_mut().unwrap().remove("requestBody");
+
+    let request: AgentApiRequest = assert_round_trip(&fixture.to_string());
+
+    assert!(request.request_body.is_none());
+}
+
+#[test]
+fn api_response_round_trip() {
+    let response: AgentApiResponse = assert_round_trip(include_str!("fixtures/api-response.json"));
+
+    assert_eq!(response.response.http_status_code, 201);
+    assert!(response
+        .response
+        .response_body
+        .content
+        .contains_key("application/json"));
+}
+
+#[test]
+fn function_request_round_trip() {
+    let request: AgentRequest = assert_round_trip(include_str!("fixtures/function-request.json"));
+
+    assert_eq!(request.function, "PrepareIceCream");
+    assert_eq!(request.parameters[0].name, "flavor");
+    assert_eq!(request.session_attributes["clientName"], "Nancy");
+}
+
+#[test]
+fn function_response_round_trip() {
+    let response: AgentResponse =
+        assert_round_trip(include_str!("fixtures/function-response.json"));
+
+    let ResponseType::Body { response_body } = response.response.function_response.response_type
+    else {
+        panic!("expected a response body");
+    };
+    assert_eq!(
+        response_body.content["TEXT"].body,
+        "Ice cream of Vanilla was made."
+    );
+}
+
+#[test]
+fn function_state_response_round_trip() {
+    let response: AgentResponse =
+        assert_round_trip(include_str!("fixtures/function-state-response.json"));
+
+    let ResponseType::State {
+        response_state,
+        response_body,
+    } = response.response.function_response.response_type
+    else {
+        panic!("expected a response state");
+    };
+    assert_eq!(response_state, ResponseState::Reprompt);
+    assert!(response_body.is_some());
+}
+
+#[test]
+fn response_states_serialize_uppercase() {
+    assert_eq!(
+        serde_json::to_value(ResponseState::Failure).unwrap(),
+        "FAILURE"
+    );
+    assert_eq!(
+        serde_json::to_value(ResponseState::Reprompt).unwrap(),
+        "REPROMPT"
+    );
+}
+
+#[test]
+fn parameter_type_round_trip() {
+    for name in ["string", "number", "integer", "boolean", "array", "object"] {
+        let parameter_type: ParameterType = serde_json::from_value(Value::from(name)).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&parameter_type).unwrap(),
+            Value::from(name)
+        );
+    }
+
+    let unknown: ParameterType = serde_json::from_str(r#""object""#).unwrap();
+    assert_eq!(unknown, ParameterType::Unknown("object".to_string()));
+}
@@ -0,0 +1,121 @@
+use serde::Deserialize;
+use std::{collections::HashSet, fmt};
+
+/// Catalog used when no "FLAVOR_CATALOG" is configured, see [`crate::config::Config`].
+pub const DEFAULT_CATALOG: &str = include_str!("../flavors.yaml");
+
+/// Flavors the ice cream maker knows how to prepare.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct FlavorCatalog {
+    flavors: Vec<Flavor>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Flavor {
+    pub name: String,
+    /// Other names clients use for this flavor, e.g. "mint chip".
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(default)]
+    pub allergens: Vec<String>,
+    /// Servings left, the flavor is out of stock when it is 0.
+    ///
+    /// Read-only: preparing ice creams doesn't decrement it, see
+    /// [`crate::machine::SimulatedMachine`].
+    pub stock: u32,
+}
+
+/// Reason a requested flavor can't be prepared.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Unavailable<'a> {
+    Unknown(String),
+    OutOfStock(&'a Flavor),
+}
+
+impl FlavorCatalog {
+    /// Parses a catalog from YAML, or JSON since it is valid YAML too.
+    pub fn from_yaml(source: &str) -> Result<Self, CatalogError> {
+        let catalog: FlavorCatalog =
+            serde_yaml::from_str(source).map_err(|error| CatalogError::Parse(error.to_string()))?;
+        catalog.validate()?;
+
+        Ok(catalog)
+    }
+
+    pub fn flavors(&self) -> &[Flavor] {
+        &self.flavors
+    }
+
+    /// Flavors with stock left, in catalog order.
+    pub fn available(&self) -> impl Iterator<Item = &Flavor> {
+        self.flavors.iter().filter(|flavor| flavor.stock > 0)
+    }
+
+    /// Finds a flavor by its name or one of its aliases, ignoring case and surrounding spaces.
+    pub fn find(&self, requested: &str) -> Option<&Flavor> {
+        let requested = normalize(requested);
+
+        self.flavors.iter().find(|flavor| {
+            normalize(&flavor.name) == requested
+                || flavor
+                    .aliases
+                    .iter()
+                    .any(|alias| normalize(alias) == requested)
+        })
+    }
+
+    /// Returns the flavor to prepare for `requested`, or why it can't be prepared.
+    pub fn check(&self, requested: &str) -> Result<&Flavor, Unavailable<'_>> {
+        match self.find(requested) {
+            Some(flavor) if flavor.stock > 0 => Ok(flavor),
+            Some(flavor) => Err(Unavailable::OutOfStock(flavor)),
+            None => Err(Unavailable::Unknown(requested.to_string())),
+        }
+    }
+
+    fn validate(&self) -> Result<(), CatalogError> {
+        if self.flavors.is_empty() {
+            return Err(CatalogError::Empty);
+        }
+
+        let mut names = HashSet::new();
+        for flavor in &self.flavors {
+            for name in std::iter::once(&flavor.name).chain(&flavor.aliases) {
+                if !names.insert(normalize(name)) {
+                    return Err(CatalogError::DuplicatedName(name.clone()));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CatalogError {
+    Parse(String),
+    Empty,
+    /// The same name is used by two flavors, or twice by the same one.
+    DuplicatedName(String),
+}
+
+impl fmt::Display for CatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CatalogError::Parse(error) => write!(f, "Invalid flavor catalog: {error}"),
+            CatalogError::Empty => write!(f, "Invalid flavor catalog: no flavors defined"),
+            CatalogError::DuplicatedName(name) => {
+                write!(
+                    f,
+                    "Invalid flavor catalog: \"{name}\" is defined more than once"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for CatalogError {}
@@ -0,0 +1,48 @@
+use crate::catalog::{FlavorCatalog, DEFAULT_CATALOG};
+use std::fmt;
+
+/// Configuration of the ice cream maker lambda function, read once on cold start.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    /// Taken from the "FLAVOR_CATALOG" environment variable, [`DEFAULT_CATALOG`] when it isn't set.
+    pub flavor_catalog: FlavorCatalog,
+}
+
+impl Config {
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Reads the configuration from the given variables.
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self, ConfigError> {
+        let mut errors = Vec::new();
+        let source = var("FLAVOR_CATALOG");
+        let flavor_catalog =
+            match FlavorCatalog::from_yaml(source.as_deref().unwrap_or(DEFAULT_CATALOG)) {
+                Ok(flavor_catalog) => Some(flavor_catalog),
+                Err(error) => {
+                    errors.push(format!(
+                        r#""FLAVOR_CATALOG" environment variable: {error}."#
+                    ));
+                    None
+                }
+            };
+
+        match flavor_catalog {
+            Some(flavor_catalog) if errors.is_empty() => Ok(Config { flavor_catalog }),
+            _ => Err(ConfigError(errors)),
+        }
+    }
+}
+
+/// Every problem found in the configuration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigError(pub Vec<String>);
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid configuration: {}", self.0.join(" "))
+    }
+}
+
+impl std::error::Error for ConfigError {}
@@ -0,0 +1,130 @@
+pub mod catalog;
+pub mod config;
+pub mod machine;
+
+use bedrock_agent_events::{
+    function::{
+        AgentRequest, AgentResponse, ContentType, FunctionResponse, Response, ResponseBody,
+        ResponseState, ResponseType,
+    },
+    MESSAGE_VERSION,
+};
+use catalog::{FlavorCatalog, Unavailable};
+use lambda_runtime::{
+    tracing::{self, instrument},
+    Error, LambdaEvent,
+};
+use machine::IceCreamMachine;
+use std::collections::HashMap;
+
+#[instrument(name = "ice_cream_maker", skip(event, catalog, machine), fields(req_id = %event.context.request_id))]
+pub async fn handler<M: IceCreamMachine>(
+    event: LambdaEvent<AgentRequest>,
+    catalog: &FlavorCatalog,
+    machine: &M,
+) -> Result<AgentResponse, Error> {
+    tracing::info!(
+        "IceCreamMaker handler invoked with payload: {:?}",
+        event.payload
+    );
+
+    let agent_request = event.payload;
+    let response_type = match agent_request.param::<String>("flavor") {
+        Ok(requested) => prepare(&requested, catalog, machine).await,
+        Err(error) => {
+            tracing::warn!("Invalid request: {}", error);
+
+            ResponseType::State {
+                response_state: ResponseState::Reprompt,
+                response_body: Some(text_body(error.to_string())),
+            }
+        }
+    };
+
+    let response = AgentResponse {
+        message_version: MESSAGE_VERSION.to_string(),
+        session_attributes: agent_request.session_attributes,
+        prompt_session_attributes: agent_request.prompt_session_attributes,
+        response: Response {
+            action_group: agent_request.action_group,
+            function: agent_request.function,
+            function_response: FunctionResponse { response_type },
+        },
+    };
+
+    tracing::info!("Response: {:?}", serde_json::to_string(&response)?);
+
+    Ok(response)
+}
+
+async fn prepare<M: IceCreamMachine>(
+    requested: &str,
+    catalog: &FlavorCatalog,
+    machine: &M,
+) -> ResponseType {
+    let flavor = match catalog.check(requested) {
+        Ok(flavor) => flavor,
+        Err(unavailable) => {
+            tracing::warn!("Requested flavor can't be prepared: {:?}", unavailable);
+
+            return ResponseType::State {
+                response_state: ResponseState::Reprompt,
+                response_body: Some(text_body(unavailable_message(&unavailable, catalog))),
+            };
+        }
+    };
+
+    tracing::info!("Preparing ice cream of {} flavor", flavor.name);
+
+    match machine.prepare(flavor).await {
+        Ok(()) => ResponseType::Body {
+            response_body: text_body(prepared_message(&flavor.name, &flavor.allergens)),
+        },
+        Err(error) => {
+            tracing::error!("Ice cream of {} couldn't be made: {}", flavor.name, error);
+
+            ResponseType::State {
+                response_state: ResponseState::Failure,
+                response_body: Some(text_body(format!(
+                    "Ice cream of {} couldn't be made. {error}.",
+                    flavor.name
+                ))),
+            }
+        }
+    }
+}
+
+fn prepared_message(flavor: &str, allergens: &[String]) -> String {
+    if allergens.is_empty() {
+        format!("Ice cream of {flavor} was made.")
+    } else {
+        format!(
+            "Ice cream of {flavor} was made. It contains: {}.",
+            allergens.join(", ")
+        )
+    }
+}
+
+fn unavailable_message(unavailable: &Unavailable, catalog: &FlavorCatalog) -> String {
+    let reason = match unavailable {
+        Unavailable::Unknown(flavor) => format!("{flavor} is not a flavor we can prepare."),
+        Unavailable::OutOfStock(flavor) => format!("{} is out of stock.", flavor.name),
+    };
+    let available = catalog
+        .available()
+        .map(|flavor| flavor.name.as_str())
+        .collect::<Vec<_>>();
+
+    if available.is_empty() {
+        format!("{reason} There are no flavors available right now.")
+    } else {
+        format!("{reason} Available flavors are: {}.", available.join(", "))
+    }
+}
+
+fn text_body(body: String) -> ResponseBody {
+    let mut content = HashMap::new();
+    content.insert("TEXT".to_string(), ContentType { body });
+
+    ResponseBody { content }
+}
@@ -0,0 +1,49 @@
+use crate::catalog::Flavor;
+use std::{fmt, future::Future};
+
+/// The ice cream machine operated by the maker.
+pub trait IceCreamMachine {
+    /// Prepares one ice cream of the given flavor.
+    fn prepare(&self, flavor: &Flavor) -> impl Future<Output = Result<(), MachineError>> + Send;
+}
+
+/// Machine used by the lab, which has no hardware behind it and always succeeds.
+///
+/// It doesn't keep track of the servings it prepares, so the stock of the catalog never goes
+/// down. Lambda instances don't share memory, a machine that does would need to keep the stock
+/// in a store like DynamoDB.
+#[derive(Debug, Default, Clone)]
+pub struct SimulatedMachine;
+
+impl IceCreamMachine for SimulatedMachine {
+    async fn prepare(&self, _flavor: &Flavor) -> Result<(), MachineError> {
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum MachineError {
+    /// The machine can't take requests right now, e.g. it is being cleaned.
+    OutOfService(String),
+    Backend(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl fmt::Display for MachineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MachineError::OutOfService(reason) => {
+                write!(f, "Ice cream machine out of service: {reason}")
+            }
+            MachineError::Backend(error) => write!(f, "Ice cream machine failed: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for MachineError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MachineError::Backend(error) => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
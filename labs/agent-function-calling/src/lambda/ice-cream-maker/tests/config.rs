@@ -0,0 +1,31 @@
+use ice_cream_maker::config::{Config, ConfigError};
+
+#[test]
+fn uses_the_default_catalog_when_not_configured() {
+    let config = Config::from_vars(|_| None).unwrap();
+
+    assert!(config.flavor_catalog.find("Vanilla").is_some());
+}
+
+#[test]
+fn reads_the_catalog_from_the_environment() {
+    let config = Config::from_vars(|name| {
+        (name == "FLAVOR_CATALOG")
+            .then(|| r#"{"flavors": [{"name": "Pistachio", "stock": 3}]}"#.to_string())
+    })
+    .unwrap();
+
+    assert_eq!(config.flavor_catalog.flavors().len(), 1);
+    assert!(config.flavor_catalog.find("pistachio").is_some());
+}
+
+#[test]
+fn reports_invalid_catalogs() {
+    assert_eq!(
+        Config::from_vars(|_| Some("flavors: []".to_string())),
+        Err(ConfigError(vec![
+            r#""FLAVOR_CATALOG" environment variable: Invalid flavor catalog: no flavors defined."#
+                .to_string()
+        ]))
+    );
+}
@@ -0,0 +1,275 @@
+use bedrock_agent_events::function::{AgentRequest, AgentResponse, ResponseState, ResponseType};
+use ice_cream_maker::{
+    catalog::{CatalogError, Flavor, FlavorCatalog, Unavailable},
+    handler,
+    machine::{IceCreamMachine, MachineError, SimulatedMachine},
+};
+use lambda_runtime::{Context, LambdaEvent};
+use serde_json::{json, Value};
+
+const CATALOG: &str = r#"
+flavors:
+  - name: Vanilla
+    allergens: [milk]
+    stock: 10
+  - name: Mint Chocolate Chip
+    aliases: [mint chip]
+    allergens: [milk, soy]
+    stock: 5
+  - name: Strawberry
+    stock: 0
+"#;
+
+struct BrokenMachine;
+
+impl IceCreamMachine for BrokenMachine {
+    async fn prepare(&self, _flavor: &Flavor) -> Result<(), MachineError> {
+        Err(MachineError::OutOfService(
+            "it is being cleaned".to_string(),
+        ))
+    }
+}
+
+fn prepare_request(parameters: Value) -> AgentRequest {
+    let mut request: Value =
+        serde_json::from_str(include_str!("fixtures/prepare-ice-cream.json")).unwrap();
+    request["parameters"] = parameters;
+
+    serde_json::from_value(request).expect("valid agent request")
+}
+
+async fn respond<M: IceCreamMachine>(parameters: Value, machine: &M) -> AgentResponse {
+    let catalog = FlavorCatalog::from_yaml(CATALOG).expect("valid catalog");
+
+    handler(
+        LambdaEvent::new(prepare_request(parameters), Context::default()),
+        &catalog,
+        machine,
+    )
+    .await
+    .expect("handler succeeds")
+}
+
+async fn call<M: IceCreamMachine>(parameters: Value, machine: &M) -> ResponseType {
+    respond(parameters, machine)
+        .await
+        .response
+        .function_response
+        .response_type
+}
+
+async fn prepare<M: IceCreamMachine>(flavor: &str, machine: &M) -> ResponseType {
+    call(flavor_parameter(flavor), machine).await
+}
+
+fn flavor_parameter(flavor: &str) -> Value {
+    json!([{ "name": "flavor", "type": "string", "value": flavor }])
+}
+
+/// Checks the response sent to the agent is exactly the one in the golden file.
+async fn assert_golden<M: IceCreamMachine>(flavor: &str, machine: &M, golden: &str) {
+    let expected: Value = serde_json::from_str(golden).expect("golden file is valid JSON");
+    let response = respond(flavor_parameter(flavor), machine).await;
+
+    assert_eq!(serde_json::to_value(&response).unwrap(), expected);
+}
+
+fn text(response_type: &ResponseType) -> &str {
+    let body = match response_type {
+        ResponseType::Body { response_body } => response_body,
+        ResponseType::State { response_body, .. } => {
+            response_body.as_ref().expect("state with a body")
+        }
+    };
+
+    &body.content["TEXT"].body
+}
+
+#[test]
+fn finds_flavors_by_name_or_alias_ignoring_case() {
+    let catalog = FlavorCatalog::from_yaml(CATALOG).unwrap();
+
+    assert_eq!(catalog.find(" vanilla ").unwrap().name, "Vanilla");
+    assert_eq!(
+        catalog.find("Mint Chip").unwrap().name,
+        "Mint Chocolate Chip"
+    );
+    assert_eq!(catalog.find("Pistachio"), None);
+}
+
+#[test]
+fn checks_availability() {
+    let catalog = FlavorCatalog::from_yaml(CATALOG).unwrap();
+
+    assert_eq!(catalog.check("vanilla").unwrap().name, "Vanilla");
+    assert!(matches!(
+        catalog.check("strawberry"),
+        Err(Unavailable::OutOfStock(flavor)) if flavor.name == "Strawberry"
+    ));
+    assert_eq!(
+        catalog.check("Pistachio"),
+        Err(Unavailable::Unknown("Pistachio".to_string()))
+    );
+    assert_eq!(
+        catalog
+            .available()
+            .map(|flavor| flavor.name.as_str())
+            .collect::<Vec<_>>(),
+        ["Vanilla", "Mint Chocolate Chip"]
+    );
+}
+
+#[test]
+fn default_catalog_is_valid() {
+    let catalog = FlavorCatalog::from_yaml(ice_cream_maker::catalog::DEFAULT_CATALOG).unwrap();
+
+    assert_eq!(catalog.flavors().len(), 5);
+}
+
+#[test]
+fn rejects_invalid_catalogs() {
+    assert_eq!(
+        FlavorCatalog::from_yaml("flavors: []"),
+        Err(CatalogError::Empty)
+    );
+    assert_eq!(
+        FlavorCatalog::from_yaml(
+            r#"{"flavors": [{"name": "Vanilla", "stock": 1}, {"name": "Choco", "aliases": ["vanilla"], "stock": 1}]}"#
+        ),
+        Err(CatalogError::DuplicatedName("vanilla".to_string()))
+    );
+    assert!(matches!(
+        FlavorCatalog::from_yaml("flavors: [{name: Vanilla}]"),
+        Err(CatalogError::Parse(_))
+    ));
+}
+
+#[tokio::test]
+async fn prepares_available_flavors() {
+    let response_type = prepare("mint chip", &SimulatedMachine).await;
+
+    assert!(matches!(response_type, ResponseType::Body { .. }));
+    assert_eq!(
+        text(&response_type),
+        "Ice cream of Mint Chocolate Chip was made. It contains: milk, soy."
+    );
+}
+
+#[tokio::test]
+async fn reprompts_unknown_flavors_with_the_available_ones() {
+    let response_type = prepare("Pistachio", &SimulatedMachine).await;
+
+    assert!(matches!(
+        response_type,
+        ResponseType::State {
+            response_state: ResponseState::Reprompt,
+            ..
+        }
+    ));
+    assert_eq!(
+        text(&response_type),
+        "Pistachio is not a flavor we can prepare. Available flavors are: Vanilla, Mint Chocolate Chip."
+    );
+}
+
+#[tokio::test]
+async fn reprompts_out_of_stock_flavors() {
+    let response_type = prepare("strawberry", &SimulatedMachine).await;
+
+    assert!(matches!(
+        response_type,
+        ResponseType::State {
+            response_state: ResponseState::Reprompt,
+            ..
+        }
+    ));
+    assert_eq!(
+        text(&response_type),
+        "Strawberry is out of stock. Available flavors are: Vanilla, Mint Chocolate Chip."
+    );
+}
+
+#[tokio::test]
+async fn reports_machine_errors_as_failures() {
+    let response_type = prepare("Vanilla", &BrokenMachine).await;
+
+    assert!(matches!(
+        response_type,
+        ResponseType::State {
+            response_state: ResponseState::Failure,
+            ..
+        }
+    ));
+    assert_eq!(
+        text(&response_type),
+        "Ice cream of Vanilla couldn't be made. Ice cream machine out of service: it is being cleaned."
+    );
+}
+
+#[tokio::test]
+async fn looks_up_the_flavor_parameter_by_name() {
+    let response_type = call(
+        json!([
+            { "name": "scoops", "type": "integer", "value": "2" },
+            { "name": "flavor", "type": "string", "value": "Vanilla" }
+        ]),
+        &SimulatedMachine,
+    )
+    .await;
+
+    assert_eq!(
+        text(&response_type),
+        "Ice cream of Vanilla was made. It contains: milk."
+    );
+}
+
+#[tokio::test]
+async fn reprompts_naming_the_missing_parameter() {
+    let response_type = call(
+        json!([{ "name": "flavour", "type": "string", "value": "Vanilla" }]),
+        &SimulatedMachine,
+    )
+    .await;
+
+    assert!(matches!(
+        response_type,
+        ResponseType::State {
+            response_state: ResponseState::Reprompt,
+            ..
+        }
+    ));
+    assert_eq!(
+        text(&response_type),
+        r#"Missing required parameter "flavor"."#
+    );
+}
+
+#[tokio::test]
+async fn prepared_response_golden_file() {
+    assert_golden(
+        "Vanilla",
+        &SimulatedMachine,
+        include_str!("fixtures/prepared-response.json"),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn reprompt_response_golden_file() {
+    assert_golden(
+        "Pistachio",
+        &SimulatedMachine,
+        include_str!("fixtures/reprompt-response.json"),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn failure_response_golden_file() {
+    assert_golden(
+        "Vanilla",
+        &BrokenMachine,
+        include_str!("fixtures/failure-response.json"),
+    )
+    .await;
+}
@@ -0,0 +1,6 @@
+/// Prints the OpenAPI document of the waiter action group, consumed by agent-caller.
+///
+/// Usage: `cargo run -p waiter --features schema --bin waiter-openapi > waiter/schemas/waiter.yaml`
+fn main() {
+    print!("{}", waiter::routes::openapi_yaml());
+}
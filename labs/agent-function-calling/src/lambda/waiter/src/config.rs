@@ -0,0 +1,51 @@
+use std::fmt;
+
+/// Configuration of the waiter lambda function, read once on cold start.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    /// DynamoDB table where orders are stored.
+    pub table_name: String,
+}
+
+impl Config {
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Reads the configuration from the given variables.
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self, ConfigError> {
+        let mut errors = Vec::new();
+        let table_name = required(&var, "TABLE_NAME", &mut errors);
+
+        match table_name {
+            Some(table_name) if errors.is_empty() => Ok(Config { table_name }),
+            _ => Err(ConfigError(errors)),
+        }
+    }
+}
+
+/// Value of a variable that must be set and not blank.
+fn required(
+    var: &impl Fn(&str) -> Option<String>,
+    name: &str,
+    errors: &mut Vec<String>,
+) -> Option<String> {
+    let value = var(name).filter(|value| !value.trim().is_empty());
+    if value.is_none() {
+        errors.push(format!(r#"Missing "{name}" environment variable."#));
+    }
+
+    value
+}
+
+/// Every problem found in the configuration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigError(pub Vec<String>);
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid configuration: {}", self.0.join(" "))
+    }
+}
+
+impl std::error::Error for ConfigError {}
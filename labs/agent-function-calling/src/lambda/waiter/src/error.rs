@@ -0,0 +1,112 @@
+use bedrock_agent_events::BodyError;
+#[cfg(feature = "schema")]
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::fmt;
+
+/// Errors the waiter reports back to the agent as part of the response, so it can reason about them.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApiError {
+    MissingParameter(&'static str),
+    InvalidRequestBody(BodyError),
+    OrderNotFound(String),
+    FlavorNotInOrder {
+        order_id: String,
+        flavor: String,
+    },
+    /// Another request updated the order at the same time.
+    OrderConflict(String),
+    RouteNotFound {
+        http_method: String,
+        api_path: String,
+    },
+    MethodNotAllowed {
+        http_method: String,
+        api_path: String,
+        allowed_methods: Vec<String>,
+    },
+}
+
+/// JSON body sent to the agent when a request fails.
+#[derive(Serialize, Debug)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorBody {
+    /// Stable identifier of the error, e.g. `ORDER_NOT_FOUND`.
+    pub error_code: &'static str,
+    /// What went wrong, for the agent to explain it to the client.
+    pub message: String,
+}
+
+impl ApiError {
+    pub fn http_status_code(&self) -> i32 {
+        match self {
+            ApiError::MissingParameter(_) | ApiError::InvalidRequestBody(_) => 400,
+            ApiError::OrderNotFound(_)
+            | ApiError::FlavorNotInOrder { .. }
+            | ApiError::RouteNotFound { .. } => 404,
+            ApiError::MethodNotAllowed { .. } => 405,
+            ApiError::OrderConflict(_) => 409,
+        }
+    }
+
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            ApiError::MissingParameter(_) => "MISSING_PARAMETER",
+            ApiError::InvalidRequestBody(_) => "INVALID_REQUEST_BODY",
+            ApiError::OrderNotFound(_) => "ORDER_NOT_FOUND",
+            ApiError::FlavorNotInOrder { .. } => "FLAVOR_NOT_IN_ORDER",
+            ApiError::OrderConflict(_) => "ORDER_CONFLICT",
+            ApiError::RouteNotFound { .. } => "ROUTE_NOT_FOUND",
+            ApiError::MethodNotAllowed { .. } => "METHOD_NOT_ALLOWED",
+        }
+    }
+
+    pub fn body(&self) -> ErrorBody {
+        ErrorBody {
+            error_code: self.error_code(),
+            message: self.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::MissingParameter(name) => write!(f, "Missing {name:?} parameter."),
+            ApiError::InvalidRequestBody(error) => error.fmt(f),
+            ApiError::OrderNotFound(order_id) => write!(f, "Order {order_id} not found."),
+            ApiError::FlavorNotInOrder { order_id, flavor } => {
+                write!(
+                    f,
+                    "Order {order_id} has no ice cream of {flavor} to remove."
+                )
+            }
+            ApiError::OrderConflict(order_id) => write!(
+                f,
+                "Order {order_id} was changed by another request, try again."
+            ),
+            ApiError::RouteNotFound {
+                http_method,
+                api_path,
+            } => write!(f, "Unknown action {http_method} {api_path}."),
+            ApiError::MethodNotAllowed {
+                http_method,
+                api_path,
+                allowed_methods,
+            } => write!(
+                f,
+                "Method {http_method} is not allowed for {api_path}, use {}.",
+                allowed_methods.join(" or ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl From<BodyError> for ApiError {
+    fn from(error: BodyError) -> Self {
+        ApiError::InvalidRequestBody(error)
+    }
+}
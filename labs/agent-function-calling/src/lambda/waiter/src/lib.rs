@@ -0,0 +1,75 @@
+pub mod config;
+pub mod error;
+pub mod openapi;
+pub mod order;
+pub mod repository;
+pub mod router;
+pub mod routes;
+pub mod types;
+
+use bedrock_agent_events::{
+    api::{AgentApiRequest, AgentApiResponse, ApiResponse, ResponseBody, ResponseBodyContentType},
+    MESSAGE_VERSION,
+};
+use error::ApiError;
+use lambda_runtime::{
+    tracing::{self, instrument},
+    Error, LambdaEvent,
+};
+use router::{Reply, Router};
+use std::collections::HashMap;
+
+#[instrument(name = "waiter_handler", skip(event, router, state), fields(req_id = %event.context.request_id))]
+pub async fn handler<S>(
+    event: LambdaEvent<AgentApiRequest>,
+    router: &Router<S>,
+    state: &S,
+) -> Result<AgentApiResponse, Error> {
+    tracing::info!("waiter handler invoked with payload: {:#?}", event);
+
+    let agent_request = event.payload;
+    let Reply {
+        http_status_code,
+        body,
+    } = match router.dispatch(&agent_request, state).await {
+        Ok(reply) => reply,
+        Err(error) => match error.downcast::<ApiError>() {
+            Ok(api_error) => {
+                tracing::warn!("Request failed: {}", api_error);
+
+                Reply::new(
+                    api_error.http_status_code(),
+                    serde_json::to_value(api_error.body())?,
+                )
+            }
+            Err(error) => return Err(error),
+        },
+    };
+
+    let mut response_content = HashMap::new();
+    response_content.insert(
+        "application/json".to_string(),
+        ResponseBodyContentType {
+            body: body.to_string(),
+        },
+    );
+
+    let response = AgentApiResponse {
+        message_version: MESSAGE_VERSION.to_string(),
+        session_attributes: agent_request.session_attributes,
+        prompt_session_attributes: agent_request.prompt_session_attributes,
+        response: ApiResponse {
+            action_group: agent_request.action_group,
+            api_path: agent_request.api_path,
+            http_method: agent_request.http_method,
+            http_status_code,
+            response_body: ResponseBody {
+                content: response_content,
+            },
+        },
+    };
+
+    tracing::info!("Response: {:?}", serde_json::to_string(&response)?);
+
+    Ok(response)
+}
@@ -1,82 +1,5 @@
-mod types;
-
-use std::collections::HashMap;
-use lambda_runtime::{
-    run, service_fn,
-    tracing::{self, instrument},
-    Error, LambdaEvent,
-};
-use types::{AgentApiRequest, AgentApiResponse};
-
-#[instrument(name = "waiter_handler", skip(event), fields(req_id = %event.context.request_id))]
-async fn handler(event: LambdaEvent<AgentApiRequest>) -> Result<AgentApiResponse, Error> {
-    tracing::info!("waiter handler invoked with payload: {:#?}", event);
-
-    let agent_request = event.payload;
-    let api_path = agent_request.api_path.as_str();
-    let order_id = event.context.request_id;
-    let mut response_content = HashMap::new();
-    let mut http_status_code = 201;
-
-    if api_path == "/start-order" {
-        response_content.insert(
-            "application/json".to_string(),
-            types::ResponseBodyContentType {
-                body: serde_json::json!({
-                    "orderId": order_id
-                }).to_string(),
-            },
-        );
-    } else if api_path.starts_with("/add-ice-cream/") {
-        response_content.insert(
-            "application/json".to_string(),
-            types::ResponseBodyContentType {
-                body: serde_json::json!({
-                    "message": format!("Ice cream added to order {order_id}"),
-                }).to_string()
-            },
-        );
-    } else if api_path.starts_with("/remove-ice-cream/") {
-        http_status_code = 200;
-        response_content.insert(
-            "application/json".to_string(),
-            types::ResponseBodyContentType {
-                body: serde_json::json!({
-                    "message": format!("Ice cream removed from order {order_id}"),
-                }).to_string()
-            },
-        );
-    } else {
-        http_status_code = 500;
-        response_content.insert(
-            "application/json".to_string(),
-            types::ResponseBodyContentType {
-                body: serde_json::json!({
-                    "message": "Unknown action",
-                }).to_string()
-            },
-        );
-    }
-
-    let response = AgentApiResponse {
-        message_version: "1.0".to_string(),
-        session_attributes: agent_request.session_attributes,
-        prompt_session_attributes: agent_request.prompt_session_attributes,
-        response: types::ApiResponse {
-            action_group: agent_request.action_group,
-            api_path: agent_request.api_path,
-            http_method: agent_request.http_method,
-            http_status_code,
-            response_body: types::ResponseBody {
-                content: response_content,
-            },
-        },
-    };
-
-    tracing::info!("Response: {:?}", serde_json::to_string(&response)?);
-
-    Ok(response)
-}
+use lambda_runtime::{run, service_fn, tracing, Error};
+use waiter::{config::Config, handler, repository::DynamoDbOrderRepository, routes};
 
 #[tokio::main]
 async fn main() -> Result<(), Error> {
@@ -94,8 +17,12 @@ async fn main() -> Result<(), Error> {
         .with_target(false)
         .init();
 
-    // let sdk_config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
-    let func = service_fn(handler);
+    let config = Config::from_env().inspect_err(|error| tracing::error!("{error}"))?;
+    let sdk_config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+    let dynamodb_client = aws_sdk_dynamodb::Client::new(&sdk_config);
+    let repository = DynamoDbOrderRepository::new(dynamodb_client, config.table_name);
+    let router = routes::router();
+    let func = service_fn(|event| handler(event, &router, &repository));
 
     run(func).await
 }
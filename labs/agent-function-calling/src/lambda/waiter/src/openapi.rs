@@ -0,0 +1,279 @@
+/**
+ * OpenAPI 3.0 document of an action group, generated from its routes.
+ *
+ * Each route declares its [`Operation`], while the request and response body schemas
+ * come from the types its handler takes and returns, so the document the agent gets
+ * can't diverge from what the lambda does.
+ *
+ * Generating the document needs the `schema` feature, so the lambda doesn't ship its dependencies.
+ */
+#[cfg(feature = "schema")]
+use openapiv3::{
+    Info, MediaType, OpenAPI, ParameterData, ParameterSchemaOrContent, PathItem, PathStyle,
+    ReferenceOr, RequestBody, Response, Responses, Schema, StatusCode,
+};
+#[cfg(feature = "schema")]
+use schemars::{gen::SchemaSettings, JsonSchema};
+
+/// Content type of every request and response body.
+pub const JSON_CONTENT_TYPE: &str = "application/json";
+
+/// Describes an operation of the action group for the agent.
+#[derive(Debug, Clone)]
+pub struct Operation {
+    pub http_method: &'static str,
+    pub path_template: &'static str,
+    pub operation_id: &'static str,
+    pub summary: &'static str,
+    pub description: &'static str,
+    /// Name and description of the path parameters described, see [`Operation::path_params`].
+    pub path_param_descriptions: Vec<(&'static str, &'static str)>,
+    /// Status code of successful responses.
+    pub http_status_code: i32,
+    pub response_description: &'static str,
+    /// Status code and description of the responses replied with an [`ErrorBody`](crate::error::ErrorBody).
+    pub error_responses: Vec<(i32, &'static str)>,
+    #[cfg(feature = "schema")]
+    pub(crate) request_body: Option<Schema>,
+    #[cfg(feature = "schema")]
+    pub(crate) response_body: Option<Schema>,
+}
+
+impl Operation {
+    pub fn new(
+        http_method: &'static str,
+        path_template: &'static str,
+        operation_id: &'static str,
+    ) -> Self {
+        Operation {
+            http_method,
+            path_template,
+            operation_id,
+            summary: "",
+            description: "",
+            path_param_descriptions: Vec::new(),
+            http_status_code: 200,
+            response_description: "Successful response.",
+            // Any request can miss a parameter, target an unknown path or use the wrong method.
+            error_responses: vec![
+                (
+                    400,
+                    "A parameter is missing or the request body is invalid.",
+                ),
+                (404, "No operation matches the path."),
+                (405, "The HTTP method isn't allowed for the path."),
+            ],
+            #[cfg(feature = "schema")]
+            request_body: None,
+            #[cfg(feature = "schema")]
+            response_body: None,
+        }
+    }
+
+    pub fn get(path_template: &'static str, operation_id: &'static str) -> Self {
+        Self::new("GET", path_template, operation_id)
+    }
+
+    pub fn post(path_template: &'static str, operation_id: &'static str) -> Self {
+        Self::new("POST", path_template, operation_id)
+    }
+
+    pub fn delete(path_template: &'static str, operation_id: &'static str) -> Self {
+        Self::new("DELETE", path_template, operation_id)
+    }
+
+    pub fn summary(mut self, summary: &'static str) -> Self {
+        self.summary = summary;
+        self
+    }
+
+    pub fn description(mut self, description: &'static str) -> Self {
+        self.description = description;
+        self
+    }
+
+    /// Names of the path parameters, the `{name}` segments of the path template.
+    pub fn path_params(&self) -> impl Iterator<Item = &'static str> {
+        self.path_template.split('/').filter_map(path_param_name)
+    }
+
+    /// Describes a path parameter for the agent.
+    ///
+    /// Panics when the path template has no such parameter, so descriptions can't outlive them.
+    pub fn describe_path_param(mut self, name: &'static str, description: &'static str) -> Self {
+        assert!(
+            self.path_params().any(|path_param| path_param == name),
+            "Path template {} has no parameter {name}",
+            self.path_template
+        );
+        self.path_param_descriptions.push((name, description));
+        self
+    }
+
+    /// Status code and description of successful responses.
+    pub fn responds(mut self, http_status_code: i32, description: &'static str) -> Self {
+        self.http_status_code = http_status_code;
+        self.response_description = description;
+        self
+    }
+
+    /// Status code and description of a failed response, replacing the default one of that status code.
+    pub fn fails(mut self, http_status_code: i32, description: &'static str) -> Self {
+        self.error_responses
+            .retain(|(status_code, _)| *status_code != http_status_code);
+        self.error_responses.push((http_status_code, description));
+        self.error_responses
+            .sort_by_key(|(status_code, _)| *status_code);
+        self
+    }
+
+    #[cfg(feature = "schema")]
+    fn to_openapi(&self) -> openapiv3::Operation {
+        let parameters =
+            self.path_params()
+                .map(|name| {
+                    let description = self
+                        .path_param_descriptions
+                        .iter()
+                        .find(|(described, _)| *described == name)
+                        .map(|(_, description)| description.to_string());
+
+                    ReferenceOr::Item(openapiv3::Parameter::Path {
+                        parameter_data: ParameterData {
+                            name: name.to_string(),
+                            description,
+                            required: true,
+                            deprecated: None,
+                            format: ParameterSchemaOrContent::Schema(ReferenceOr::Item(
+                                schema_for::<String>(),
+                            )),
+                            example: None,
+                            examples: Default::default(),
+                            explode: None,
+                            extensions: Default::default(),
+                        },
+                        style: PathStyle::Simple,
+                    })
+                })
+                .collect();
+
+        let request_body = self.request_body.clone().map(|schema| {
+            ReferenceOr::Item(RequestBody {
+                content: json_content(schema),
+                required: true,
+                ..Default::default()
+            })
+        });
+
+        let mut responses = Responses::default();
+        responses.responses.insert(
+            StatusCode::Code(self.http_status_code as u16),
+            ReferenceOr::Item(Response {
+                description: self.response_description.to_string(),
+                content: self
+                    .response_body
+                    .clone()
+                    .map(json_content)
+                    .unwrap_or_default(),
+                ..Default::default()
+            }),
+        );
+        for (http_status_code, description) in &self.error_responses {
+            responses.responses.insert(
+                StatusCode::Code(*http_status_code as u16),
+                ReferenceOr::Item(Response {
+                    description: description.to_string(),
+                    content: json_content(schema_for::<crate::error::ErrorBody>()),
+                    ..Default::default()
+                }),
+            );
+        }
+
+        openapiv3::Operation {
+            operation_id: Some(self.operation_id.to_string()),
+            summary: Some(self.summary.to_string()).filter(|summary| !summary.is_empty()),
+            description: Some(self.description.to_string())
+                .filter(|description| !description.is_empty()),
+            parameters,
+            request_body,
+            responses,
+            ..Default::default()
+        }
+    }
+}
+
+/// Name of the parameter of a path template segment, e.g. `orderId` for `{orderId}`.
+pub(crate) fn path_param_name(segment: &str) -> Option<&str> {
+    segment
+        .strip_prefix('{')
+        .and_then(|segment| segment.strip_suffix('}'))
+}
+
+/// Builds the document describing the given operations.
+#[cfg(feature = "schema")]
+pub fn document<'a>(
+    title: &str,
+    version: &str,
+    operations: impl IntoIterator<Item = &'a Operation>,
+) -> OpenAPI {
+    let mut openapi = OpenAPI {
+        openapi: "3.0.0".to_string(),
+        info: Info {
+            title: title.to_string(),
+            version: version.to_string(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    for operation in operations {
+        let path_item = openapi
+            .paths
+            .paths
+            .entry(operation.path_template.to_string())
+            .or_insert_with(|| ReferenceOr::Item(PathItem::default()));
+        let ReferenceOr::Item(path_item) = path_item else {
+            unreachable!("paths are only added as items");
+        };
+
+        let method = match operation.http_method.to_ascii_uppercase().as_str() {
+            "GET" => &mut path_item.get,
+            "PUT" => &mut path_item.put,
+            "POST" => &mut path_item.post,
+            "DELETE" => &mut path_item.delete,
+            "PATCH" => &mut path_item.patch,
+            unsupported => panic!("Unsupported HTTP method {unsupported}"),
+        };
+        *method = Some(operation.to_openapi());
+    }
+
+    openapi
+}
+
+/// OpenAPI 3.0 schema of `T`, with nested types inlined as Bedrock expects.
+#[cfg(feature = "schema")]
+pub fn schema_for<T: JsonSchema>() -> Schema {
+    let generator = SchemaSettings::openapi3()
+        .with(|settings| settings.inline_subschemas = true)
+        .into_generator();
+    let mut schema = generator.into_root_schema_for::<T>().schema;
+    // The type name means nothing to the agent.
+    if let Some(metadata) = schema.metadata.as_mut() {
+        metadata.title = None;
+    }
+
+    serde_json::to_value(schema)
+        .and_then(serde_json::from_value)
+        .expect("JSON schemas generated by schemars are valid OpenAPI schemas")
+}
+
+#[cfg(feature = "schema")]
+fn json_content(schema: Schema) -> openapiv3::Content {
+    openapiv3::Content::from([(
+        JSON_CONTENT_TYPE.to_string(),
+        MediaType {
+            schema: Some(ReferenceOr::Item(schema)),
+            ..Default::default()
+        },
+    )])
+}
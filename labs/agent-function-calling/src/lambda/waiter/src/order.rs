@@ -0,0 +1,104 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// An ice cream order taken by the waiter on behalf of a client.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Order {
+    pub order_id: String,
+    pub client_name: String,
+    pub items: Vec<OrderItem>,
+    pub status: OrderStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Incremented by the repository on every update, so concurrent changes don't overwrite each other.
+    pub version: u64,
+}
+
+/// How many ice creams of a given flavor were requested.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OrderItem {
+    pub flavor: String,
+    pub quantity: u32,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum OrderStatus {
+    /// Ice creams can still be added to or removed from the order.
+    Open,
+}
+
+impl OrderStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderStatus::Open => "OPEN",
+        }
+    }
+}
+
+impl std::str::FromStr for OrderStatus {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "OPEN" => Ok(OrderStatus::Open),
+            unknown => Err(format!("Unknown order status {unknown:?}")),
+        }
+    }
+}
+
+impl Order {
+    /// Starts an empty order for the given client with a freshly generated id.
+    pub fn new(client_name: impl Into<String>, now: DateTime<Utc>) -> Self {
+        Order {
+            order_id: uuid::Uuid::new_v4().to_string(),
+            client_name: client_name.into(),
+            items: Vec::new(),
+            status: OrderStatus::Open,
+            created_at: now,
+            updated_at: now,
+            version: 0,
+        }
+    }
+
+    /// Adds one ice cream of the given flavor.
+    /// Flavors are compared ignoring case, so "vanilla" and "Vanilla" count as the same item.
+    pub fn add_ice_cream(&mut self, flavor: &str, now: DateTime<Utc>) {
+        match self.find_item(flavor) {
+            Some(index) => self.items[index].quantity += 1,
+            None => self.items.push(OrderItem {
+                flavor: flavor.to_string(),
+                quantity: 1,
+            }),
+        }
+        self.updated_at = now;
+    }
+
+    /// Removes one ice cream of the given flavor.
+    /// Returns `false` when the order didn't contain that flavor.
+    pub fn remove_ice_cream(&mut self, flavor: &str, now: DateTime<Utc>) -> bool {
+        let Some(index) = self.find_item(flavor) else {
+            return false;
+        };
+
+        if self.items[index].quantity > 1 {
+            self.items[index].quantity -= 1;
+        } else {
+            self.items.remove(index);
+        }
+        self.updated_at = now;
+
+        true
+    }
+
+    /// Total amount of ice creams in the order.
+    pub fn total_ice_creams(&self) -> u32 {
+        self.items.iter().map(|item| item.quantity).sum()
+    }
+
+    fn find_item(&self, flavor: &str) -> Option<usize> {
+        self.items
+            .iter()
+            .position(|item| item.flavor.eq_ignore_ascii_case(flavor))
+    }
+}
@@ -0,0 +1,220 @@
+/**
+ * Orders stored in a DynamoDB table with `order_id` as partition key.
+ *
+ * Each order is one item:
+ * - order_id: S
+ * - client_name: S
+ * - items: L of M { flavor: S, quantity: N }
+ * - status: S
+ * - created_at: S (RFC 3339)
+ * - updated_at: S (RFC 3339)
+ * - version: N, updates only succeed if it still holds the version that was read
+ */
+use super::{OrderRepository, RepositoryError};
+use crate::order::{Order, OrderItem};
+use aws_sdk_dynamodb::{
+    operation::put_item::PutItemError,
+    types::{AttributeValue, ReturnValuesOnConditionCheckFailure},
+};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+pub struct DynamoDbOrderRepository {
+    client: aws_sdk_dynamodb::Client,
+    table_name: String,
+}
+
+impl DynamoDbOrderRepository {
+    pub fn new(client: aws_sdk_dynamodb::Client, table_name: impl Into<String>) -> Self {
+        DynamoDbOrderRepository {
+            client,
+            table_name: table_name.into(),
+        }
+    }
+}
+
+impl OrderRepository for DynamoDbOrderRepository {
+    async fn create(&self, order: &Order) -> Result<(), RepositoryError> {
+        self.client
+            .put_item()
+            .table_name(&self.table_name)
+            .set_item(Some(to_item(order)))
+            .condition_expression("attribute_not_exists(order_id)")
+            .send()
+            .await
+            .map_err(|error| {
+                if error
+                    .as_service_error()
+                    .is_some_and(|error| error.is_conditional_check_failed_exception())
+                {
+                    RepositoryError::AlreadyExists(order.order_id.clone())
+                } else {
+                    RepositoryError::Backend(error.into())
+                }
+            })?;
+
+        Ok(())
+    }
+
+    async fn get(&self, order_id: &str) -> Result<Option<Order>, RepositoryError> {
+        let output = self
+            .client
+            .get_item()
+            .table_name(&self.table_name)
+            .key("order_id", AttributeValue::S(order_id.to_string()))
+            .consistent_read(true)
+            .send()
+            .await
+            .map_err(|error| RepositoryError::Backend(error.into()))?;
+
+        output.item.as_ref().map(from_item).transpose()
+    }
+
+    async fn update(&self, order: &Order) -> Result<(), RepositoryError> {
+        let updated_order = Order {
+            version: order.version + 1,
+            ..order.clone()
+        };
+
+        self.client
+            .put_item()
+            .table_name(&self.table_name)
+            .set_item(Some(to_item(&updated_order)))
+            .condition_expression("#version = :version")
+            .expression_attribute_names("#version", "version")
+            .expression_attribute_values(":version", AttributeValue::N(order.version.to_string()))
+            .return_values_on_condition_check_failure(ReturnValuesOnConditionCheckFailure::AllOld)
+            .send()
+            .await
+            .map_err(|error| match error.as_service_error() {
+                // The stored item only comes back when it exists, with a different version.
+                Some(PutItemError::ConditionalCheckFailedException(exception)) => {
+                    if exception.item().is_some() {
+                        RepositoryError::Conflict(order.order_id.clone())
+                    } else {
+                        RepositoryError::NotFound(order.order_id.clone())
+                    }
+                }
+                _ => RepositoryError::Backend(error.into()),
+            })?;
+
+        Ok(())
+    }
+}
+
+fn to_item(order: &Order) -> HashMap<String, AttributeValue> {
+    let items = order
+        .items
+        .iter()
+        .map(|item| {
+            AttributeValue::M(HashMap::from([
+                ("flavor".to_string(), AttributeValue::S(item.flavor.clone())),
+                (
+                    "quantity".to_string(),
+                    AttributeValue::N(item.quantity.to_string()),
+                ),
+            ]))
+        })
+        .collect();
+
+    HashMap::from([
+        (
+            "order_id".to_string(),
+            AttributeValue::S(order.order_id.clone()),
+        ),
+        (
+            "client_name".to_string(),
+            AttributeValue::S(order.client_name.clone()),
+        ),
+        ("items".to_string(), AttributeValue::L(items)),
+        (
+            "status".to_string(),
+            AttributeValue::S(order.status.as_str().to_string()),
+        ),
+        (
+            "created_at".to_string(),
+            AttributeValue::S(order.created_at.to_rfc3339()),
+        ),
+        (
+            "updated_at".to_string(),
+            AttributeValue::S(order.updated_at.to_rfc3339()),
+        ),
+        (
+            "version".to_string(),
+            AttributeValue::N(order.version.to_string()),
+        ),
+    ])
+}
+
+fn from_item(item: &HashMap<String, AttributeValue>) -> Result<Order, RepositoryError> {
+    let items = get_attribute(item, "items")?
+        .as_l()
+        .map_err(|_| malformed("items", "a list"))?
+        .iter()
+        .map(|order_item| {
+            let order_item = order_item
+                .as_m()
+                .map_err(|_| malformed("items", "a list of maps"))?;
+            let quantity = get_number(order_item, "quantity")?;
+
+            Ok(OrderItem {
+                flavor: get_string(order_item, "flavor")?.to_string(),
+                quantity,
+            })
+        })
+        .collect::<Result<Vec<_>, RepositoryError>>()?;
+
+    Ok(Order {
+        order_id: get_string(item, "order_id")?.to_string(),
+        client_name: get_string(item, "client_name")?.to_string(),
+        items,
+        status: get_string(item, "status")?
+            .parse()
+            .map_err(RepositoryError::Malformed)?,
+        created_at: get_timestamp(item, "created_at")?,
+        updated_at: get_timestamp(item, "updated_at")?,
+        version: get_number(item, "version")?,
+    })
+}
+
+fn get_attribute<'a>(
+    item: &'a HashMap<String, AttributeValue>,
+    name: &str,
+) -> Result<&'a AttributeValue, RepositoryError> {
+    item.get(name)
+        .ok_or_else(|| RepositoryError::Malformed(format!("missing attribute {name:?}")))
+}
+
+fn get_string<'a>(
+    item: &'a HashMap<String, AttributeValue>,
+    name: &str,
+) -> Result<&'a str, RepositoryError> {
+    get_attribute(item, name)?
+        .as_s()
+        .map(String::as_str)
+        .map_err(|_| malformed(name, "a string"))
+}
+
+fn get_number<T: std::str::FromStr>(
+    item: &HashMap<String, AttributeValue>,
+    name: &str,
+) -> Result<T, RepositoryError> {
+    get_attribute(item, name)?
+        .as_n()
+        .map_err(|_| malformed(name, "a number"))?
+        .parse()
+        .map_err(|_| malformed(name, "a positive integer"))
+}
+
+fn get_timestamp(
+    item: &HashMap<String, AttributeValue>,
+    name: &str,
+) -> Result<DateTime<Utc>, RepositoryError> {
+    DateTime::parse_from_rfc3339(get_string(item, name)?)
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .map_err(|_| malformed(name, "an RFC 3339 timestamp"))
+}
+
+fn malformed(name: &str, expected: &str) -> RepositoryError {
+    RepositoryError::Malformed(format!("attribute {name:?} is expected to be {expected}"))
+}
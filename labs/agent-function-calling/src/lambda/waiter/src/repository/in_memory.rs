@@ -0,0 +1,49 @@
+use super::{OrderRepository, RepositoryError};
+use crate::order::Order;
+use std::{collections::HashMap, sync::Mutex};
+
+/// Keeps orders in memory, useful for tests and local runs.
+#[derive(Debug, Default)]
+pub struct InMemoryOrderRepository {
+    orders: Mutex<HashMap<String, Order>>,
+}
+
+impl InMemoryOrderRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl OrderRepository for InMemoryOrderRepository {
+    async fn create(&self, order: &Order) -> Result<(), RepositoryError> {
+        let mut orders = self.orders.lock().expect("orders lock poisoned");
+        if orders.contains_key(&order.order_id) {
+            return Err(RepositoryError::AlreadyExists(order.order_id.clone()));
+        }
+        orders.insert(order.order_id.clone(), order.clone());
+
+        Ok(())
+    }
+
+    async fn get(&self, order_id: &str) -> Result<Option<Order>, RepositoryError> {
+        let orders = self.orders.lock().expect("orders lock poisoned");
+
+        Ok(orders.get(order_id).cloned())
+    }
+
+    async fn update(&self, order: &Order) -> Result<(), RepositoryError> {
+        let mut orders = self.orders.lock().expect("orders lock poisoned");
+        let Some(stored_order) = orders.get_mut(&order.order_id) else {
+            return Err(RepositoryError::NotFound(order.order_id.clone()));
+        };
+        if stored_order.version != order.version {
+            return Err(RepositoryError::Conflict(order.order_id.clone()));
+        }
+        *stored_order = Order {
+            version: order.version + 1,
+            ..order.clone()
+        };
+
+        Ok(())
+    }
+}
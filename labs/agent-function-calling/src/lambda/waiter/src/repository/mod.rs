@@ -0,0 +1,61 @@
+mod dynamodb;
+mod in_memory;
+
+pub use dynamodb::DynamoDbOrderRepository;
+pub use in_memory::InMemoryOrderRepository;
+
+use crate::order::Order;
+use std::{fmt, future::Future};
+
+/// Storage for the orders taken by the waiter.
+pub trait OrderRepository {
+    /// Stores a new order, failing with [`RepositoryError::AlreadyExists`] if its id is taken.
+    fn create(&self, order: &Order) -> impl Future<Output = Result<(), RepositoryError>> + Send;
+
+    /// Retrieves an order by its id, `None` if it doesn't exist.
+    fn get(
+        &self,
+        order_id: &str,
+    ) -> impl Future<Output = Result<Option<Order>, RepositoryError>> + Send;
+
+    /// Replaces an existing order, storing it with its version incremented.
+    /// Fails with [`RepositoryError::NotFound`] if it doesn't exist and with [`RepositoryError::Conflict`]
+    /// if it was updated since this version of the order was retrieved.
+    fn update(&self, order: &Order) -> impl Future<Output = Result<(), RepositoryError>> + Send;
+}
+
+#[derive(Debug)]
+pub enum RepositoryError {
+    AlreadyExists(String),
+    NotFound(String),
+    /// The order was updated concurrently, retrieve it again before retrying.
+    Conflict(String),
+    /// A stored record couldn't be converted into an [`Order`].
+    Malformed(String),
+    Backend(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl fmt::Display for RepositoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RepositoryError::AlreadyExists(order_id) => {
+                write!(f, "Order {order_id} already exists")
+            }
+            RepositoryError::NotFound(order_id) => write!(f, "Order {order_id} not found"),
+            RepositoryError::Conflict(order_id) => {
+                write!(f, "Order {order_id} was updated concurrently")
+            }
+            RepositoryError::Malformed(reason) => write!(f, "Malformed order record: {reason}"),
+            RepositoryError::Backend(error) => write!(f, "Order storage failed: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for RepositoryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RepositoryError::Backend(error) => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,284 @@
+/**
+ * Routes the requests of an action group defined with an OpenAPI schema
+ * to the handler of the operation, given its HTTP method and path template.
+ *
+ * e.g. a request with `apiPath: /add-ice-cream/{orderId}` and `httpMethod: POST`
+ * is handled by the route registered for `Operation::post("/add-ice-cream/{orderId}", ..)`.
+ */
+use crate::{
+    error::ApiError,
+    openapi::{self, Operation, JSON_CONTENT_TYPE},
+};
+use bedrock_agent_events::api::AgentApiRequest;
+use lambda_runtime::Error;
+#[cfg(feature = "schema")]
+use openapiv3::{OpenAPI, Schema};
+#[cfg(feature = "schema")]
+use schemars::JsonSchema;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{future::Future, pin::Pin};
+
+/// Future returned by handlers, resolving to their response body.
+pub type HandlerFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, Error>> + Send + 'a>>;
+
+type BoxedHandler<S> =
+    Box<dyn for<'a> Fn(&'a RouteRequest<'a>, &'a S) -> HandlerFuture<'a, Reply> + Send + Sync>;
+
+/// Bodies of requests and responses, which must have a JSON schema to generate the OpenAPI
+/// document with the `schema` feature.
+#[cfg(feature = "schema")]
+pub trait Documented: JsonSchema {}
+
+#[cfg(feature = "schema")]
+impl<T: JsonSchema> Documented for T {}
+
+/// Bodies of requests and responses, which must have a JSON schema to generate the OpenAPI
+/// document with the `schema` feature.
+#[cfg(not(feature = "schema"))]
+pub trait Documented {}
+
+#[cfg(not(feature = "schema"))]
+impl<T> Documented for T {}
+
+/// HTTP status code and JSON body replied to the agent with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reply {
+    pub http_status_code: i32,
+    pub body: serde_json::Value,
+}
+
+impl Reply {
+    pub fn new(http_status_code: i32, body: serde_json::Value) -> Self {
+        Reply {
+            http_status_code,
+            body,
+        }
+    }
+}
+
+/// Request body handed to a handler.
+pub trait FromRequestBody: Sized {
+    fn from_request_body(agent_request: &AgentApiRequest) -> Result<Self, ApiError>;
+
+    /// Schema of the request body, `None` if the operation doesn't expect one.
+    #[cfg(feature = "schema")]
+    fn schema() -> Option<Schema>;
+}
+
+/// Operations without request body.
+impl FromRequestBody for () {
+    fn from_request_body(_: &AgentApiRequest) -> Result<Self, ApiError> {
+        Ok(())
+    }
+
+    #[cfg(feature = "schema")]
+    fn schema() -> Option<Schema> {
+        None
+    }
+}
+
+/// JSON request body read into `T`.
+#[derive(Debug)]
+pub struct Json<T>(pub T);
+
+impl<T: DeserializeOwned + Documented> FromRequestBody for Json<T> {
+    fn from_request_body(agent_request: &AgentApiRequest) -> Result<Self, ApiError> {
+        Ok(Json(agent_request.body(JSON_CONTENT_TYPE)?))
+    }
+
+    #[cfg(feature = "schema")]
+    fn schema() -> Option<Schema> {
+        Some(openapi::schema_for::<T>())
+    }
+}
+
+/// Request matched to a route, handed to its handler.
+pub struct RouteRequest<'a> {
+    pub agent_request: &'a AgentApiRequest,
+    path_params: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> RouteRequest<'a> {
+    /// Value of a path parameter, e.g. `orderId` for `/add-ice-cream/{orderId}`.
+    ///
+    /// The value resolved by the agent in `parameters` takes precedence over the one in the path,
+    /// since the agent usually sends the path template as is.
+    pub fn path_param(&self, name: &'static str) -> Result<&'a str, ApiError> {
+        self.agent_request
+            .parameters
+            .iter()
+            .find(|parameter| parameter.name == name)
+            .map(|parameter| parameter.value.as_str())
+            .or_else(|| {
+                self.path_params
+                    .iter()
+                    .find(|(param_name, _)| *param_name == name)
+                    .map(|(_, value)| *value)
+            })
+            .ok_or(ApiError::MissingParameter(name))
+    }
+}
+
+struct Route<S> {
+    operation: Operation,
+    handler: BoxedHandler<S>,
+}
+
+/// Routes of an action group, sharing the state `S` their handlers work with.
+pub struct Router<S> {
+    routes: Vec<Route<S>>,
+}
+
+impl<S> Default for Router<S> {
+    fn default() -> Self {
+        Router { routes: Vec::new() }
+    }
+}
+
+impl<S> Router<S> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the handler of an operation.
+    ///
+    /// The handler gets the request body `B`, e.g. [`Json`], and returns the response body `T`,
+    /// which is replied with the status code declared by the operation.
+    pub fn route<B, T, F>(
+        mut self,
+        #[cfg_attr(not(feature = "schema"), allow(unused_mut))] mut operation: Operation,
+        handler: F,
+    ) -> Self
+    where
+        B: FromRequestBody + 'static,
+        T: Serialize + Documented + 'static,
+        F: for<'a> Fn(&'a RouteRequest<'a>, B, &'a S) -> HandlerFuture<'a, T>
+            + Send
+            + Sync
+            + 'static,
+    {
+        #[cfg(feature = "schema")]
+        {
+            operation.request_body = B::schema();
+            operation.response_body = Some(openapi::schema_for::<T>());
+        }
+
+        let http_status_code = operation.http_status_code;
+        let handler = boxed(move |request, state| {
+            let response = B::from_request_body(request.agent_request)
+                .map(|body| handler(request, body, state));
+
+            Box::pin(async move {
+                let body = response?.await?;
+
+                Ok(Reply::new(http_status_code, serde_json::to_value(body)?))
+            })
+        });
+
+        self.routes.push(Route { operation, handler });
+        self
+    }
+
+    /// HTTP method and path template of every registered route.
+    pub fn routes(&self) -> impl Iterator<Item = (&'static str, &'static str)> + '_ {
+        self.routes
+            .iter()
+            .map(|route| (route.operation.http_method, route.operation.path_template))
+    }
+
+    /// OpenAPI document describing every registered route.
+    #[cfg(feature = "schema")]
+    pub fn openapi(&self, title: &str, version: &str) -> OpenAPI {
+        openapi::document(
+            title,
+            version,
+            self.routes.iter().map(|route| &route.operation),
+        )
+    }
+
+    /// Runs the handler of the route matching the request.
+    ///
+    /// Fails with [`ApiError::RouteNotFound`] when no route matches the path,
+    /// or [`ApiError::MethodNotAllowed`] when routes match the path but not the method.
+    pub async fn dispatch(
+        &self,
+        agent_request: &AgentApiRequest,
+        state: &S,
+    ) -> Result<Reply, Error> {
+        let mut allowed_methods = Vec::new();
+
+        for route in &self.routes {
+            let Some(path_params) =
+                match_path(route.operation.path_template, &agent_request.api_path)
+            else {
+                continue;
+            };
+
+            if !route
+                .operation
+                .http_method
+                .eq_ignore_ascii_case(&agent_request.http_method)
+            {
+                allowed_methods.push(route.operation.http_method.to_string());
+                continue;
+            }
+
+            let request = RouteRequest {
+                agent_request,
+                path_params,
+            };
+
+            return (route.handler)(&request, state).await;
+        }
+
+        if allowed_methods.is_empty() {
+            Err(ApiError::RouteNotFound {
+                http_method: agent_request.http_method.clone(),
+                api_path: agent_request.api_path.clone(),
+            }
+            .into())
+        } else {
+            Err(ApiError::MethodNotAllowed {
+                http_method: agent_request.http_method.clone(),
+                api_path: agent_request.api_path.clone(),
+                allowed_methods,
+            }
+            .into())
+        }
+    }
+}
+
+/// Helps the compiler infer the higher-ranked lifetimes of the handler closure.
+fn boxed<S, F>(handler: F) -> BoxedHandler<S>
+where
+    F: for<'a> Fn(&'a RouteRequest<'a>, &'a S) -> HandlerFuture<'a, Reply> + Send + Sync + 'static,
+{
+    Box::new(handler)
+}
+
+/// Matches a path against a template like `/add-ice-cream/{orderId}`,
+/// returning the path parameters found in it.
+///
+/// The template itself matches too, in that case its parameters are left for `parameters`.
+fn match_path<'a>(template: &'a str, path: &'a str) -> Option<Vec<(&'a str, &'a str)>> {
+    let mut template_segments = template.trim_end_matches('/').split('/');
+    let mut path_segments = path.trim_end_matches('/').split('/');
+    let mut path_params = Vec::new();
+
+    loop {
+        match (template_segments.next(), path_segments.next()) {
+            (None, None) => return Some(path_params),
+            (Some(template_segment), Some(path_segment)) => {
+                match openapi::path_param_name(template_segment) {
+                    Some(_) if path_segment == template_segment => {}
+                    Some(name) if !path_segment.is_empty() => {
+                        path_params.push((name, path_segment))
+                    }
+                    None if path_segment == template_segment => {}
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        }
+    }
+}
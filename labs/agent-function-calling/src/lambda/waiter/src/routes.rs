@@ -0,0 +1,132 @@
+/**
+ * Operations of the waiter action group.
+ *
+ * `schemas/waiter.yaml` is generated from these, run
+ * `cargo run -p waiter --features schema --bin waiter-openapi > waiter/schemas/waiter.yaml` after changing them.
+ */
+use crate::{
+    error::ApiError,
+    openapi::Operation,
+    order::Order,
+    repository::{OrderRepository, RepositoryError},
+    router::{Json, RouteRequest, Router},
+    types::{IceCreamBody, MessageResponse, StartOrderBody, StartOrderResponse},
+};
+use chrono::Utc;
+use lambda_runtime::{tracing, Error};
+
+pub const API_TITLE: &str = "Ice Cream Order API";
+pub const API_VERSION: &str = "1.0.0";
+
+pub fn router<R: OrderRepository + Sync>() -> Router<R> {
+    Router::new()
+        .route(
+            Operation::post("/start-order", "StartOrder")
+                .summary("Creates an order given the name of the client as input.")
+                .description("Given the name of the client as input it creates a new order.")
+                .responds(201, "Order started successfully."),
+            |request, body, repository| Box::pin(start_order(request, body, repository)),
+        )
+        .route(
+            Operation::post("/add-ice-cream/{orderId}", "AddIceCream")
+                .summary("Adds a flavor to an existing order.")
+                .description(
+                    "Adds an ice cream of a given flavor to an existing order. \
+                    The order id comes as a path parameter, while the flavor comes in the request body.",
+                )
+                .describe_path_param("orderId", "Unique ID of the order.")
+                .responds(201, "Ice cream added successfully.")
+                .fails(404, "The order doesn't exist.")
+                .fails(409, "The order was changed by another request, try again."),
+            |request, body, repository| Box::pin(add_ice_cream(request, body, repository)),
+        )
+        .route(
+            Operation::post("/remove-ice-cream/{orderId}", "RemoveIceCream")
+                .summary("Removes a flavor from an existing order.")
+                .description(
+                    "Removes an ice cream of a given flavor from an existing order. \
+                    The order id comes as a path parameter, while the flavor comes in the request body.",
+                )
+                .describe_path_param("orderId", "Unique ID of the order.")
+                .responds(200, "Ice cream removed successfully.")
+                .fails(404, "The order doesn't exist or has no ice cream of the flavor.")
+                .fails(409, "The order was changed by another request, try again."),
+            |request, body, repository| Box::pin(remove_ice_cream(request, body, repository)),
+        )
+}
+
+/// OpenAPI document of the waiter action group, as YAML.
+#[cfg(feature = "schema")]
+pub fn openapi_yaml() -> String {
+    // The document doesn't depend on the repository, any will do.
+    let openapi =
+        router::<crate::repository::InMemoryOrderRepository>().openapi(API_TITLE, API_VERSION);
+
+    serde_yaml::to_string(&openapi).expect("OpenAPI documents serialize to YAML")
+}
+
+async fn start_order<R: OrderRepository>(
+    _request: &RouteRequest<'_>,
+    Json(StartOrderBody { client_name }): Json<StartOrderBody>,
+    repository: &R,
+) -> Result<StartOrderResponse, Error> {
+    let order = Order::new(client_name.as_str(), Utc::now());
+    repository.create(&order).await?;
+
+    tracing::info!({ order_id = %order.order_id, %client_name }, "Order started.");
+
+    Ok(StartOrderResponse {
+        order_id: order.order_id,
+    })
+}
+
+async fn add_ice_cream<R: OrderRepository>(
+    request: &RouteRequest<'_>,
+    Json(IceCreamBody { flavor }): Json<IceCreamBody>,
+    repository: &R,
+) -> Result<MessageResponse, Error> {
+    let order_id = request.path_param("orderId")?;
+    let mut order = repository
+        .get(order_id)
+        .await?
+        .ok_or_else(|| ApiError::OrderNotFound(order_id.to_string()))?;
+    order.add_ice_cream(&flavor, Utc::now());
+    update_order(repository, &order).await?;
+
+    Ok(MessageResponse {
+        message: format!("Ice cream of {flavor} added to order {order_id}"),
+    })
+}
+
+async fn remove_ice_cream<R: OrderRepository>(
+    request: &RouteRequest<'_>,
+    Json(IceCreamBody { flavor }): Json<IceCreamBody>,
+    repository: &R,
+) -> Result<MessageResponse, Error> {
+    let order_id = request.path_param("orderId")?;
+    let mut order = repository
+        .get(order_id)
+        .await?
+        .ok_or_else(|| ApiError::OrderNotFound(order_id.to_string()))?;
+
+    if !order.remove_ice_cream(&flavor, Utc::now()) {
+        return Err(ApiError::FlavorNotInOrder {
+            order_id: order_id.to_string(),
+            flavor,
+        }
+        .into());
+    }
+    update_order(repository, &order).await?;
+
+    Ok(MessageResponse {
+        message: format!("Ice cream of {flavor} removed from order {order_id}"),
+    })
+}
+
+/// Stores the changed order, telling the agent to retry if it was changed concurrently.
+async fn update_order<R: OrderRepository>(repository: &R, order: &Order) -> Result<(), Error> {
+    repository.update(order).await.map_err(|error| match error {
+        RepositoryError::Conflict(order_id) => ApiError::OrderConflict(order_id).into(),
+        error => error.into(),
+    })
+}
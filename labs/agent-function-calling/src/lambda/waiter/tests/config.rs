@@ -0,0 +1,28 @@
+use std::collections::HashMap;
+use waiter::config::{Config, ConfigError};
+
+fn vars(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let vars: HashMap<String, String> = vars
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+
+    move |name| vars.get(name).cloned()
+}
+
+#[test]
+fn reads_the_table_name() {
+    let config = Config::from_vars(vars(&[("TABLE_NAME", "orders")])).unwrap();
+
+    assert_eq!(config.table_name, "orders");
+}
+
+#[test]
+fn reports_missing_table_name() {
+    assert_eq!(
+        Config::from_vars(vars(&[("TABLE_NAME", " ")])),
+        Err(ConfigError(vec![
+            r#"Missing "TABLE_NAME" environment variable."#.to_string()
+        ]))
+    );
+}
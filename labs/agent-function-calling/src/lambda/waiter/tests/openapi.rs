@@ -0,0 +1,403 @@
+/**
+ * Checks the waiter implements the operations of `schemas/waiter.yaml` as declared.
+ *
+ * The schema is generated from the waiter routes, so it must be up to date with them.
+ * Then each operation is called like the agent would, with values made up from its schema,
+ * against an order that already contains the flavor used in those values.
+ * The response status code has to be declared for the operation and its body has to
+ * validate against the declared schema.
+ * So do the errors replied to requests with an unknown path, an undeclared method or an invalid body.
+ */
+use bedrock_agent_events::api::{AgentApiRequest, ApiResponse};
+use chrono::Utc;
+use lambda_runtime::{Context, LambdaEvent};
+use openapiv3::{OpenAPI, Operation, ParameterSchemaOrContent, ReferenceOr, StatusCode};
+use serde_json::{json, Value};
+use waiter::{
+    handler,
+    order::Order,
+    repository::{InMemoryOrderRepository, OrderRepository},
+    routes,
+};
+
+const WAITER_SCHEMA: &str = include_str!("../schemas/waiter.yaml");
+
+/// Every path parameter is given the id of the seeded order.
+const ORDER_ID: &str = "9f0c5a3e-3a8e-4b0e-9a53-0a6d3c1d2b7f";
+/// Every string property without an example is given the flavor in the seeded order.
+const FLAVOR: &str = "Vanilla";
+
+fn document() -> (OpenAPI, Value) {
+    let openapi: OpenAPI = serde_yaml::from_str(WAITER_SCHEMA).expect("valid OpenAPI document");
+    let raw = serde_json::to_value(&openapi).unwrap();
+
+    (openapi, raw)
+}
+
+async fn seeded_repository() -> InMemoryOrderRepository {
+    let repository = InMemoryOrderRepository::new();
+    let mut order = Order::new("Nancy", Utc::now());
+    order.order_id = ORDER_ID.to_string();
+    order.add_ice_cream(FLAVOR, Utc::now());
+    repository.create(&order).await.unwrap();
+
+    repository
+}
+
+/// Builds the request the agent would send for the operation.
+fn agent_request(path: &str, method: &str, operation: &Operation, raw: &Value) -> AgentApiRequest {
+    let parameters: Vec<Value> = operation
+        .parameters
+        .iter()
+        .filter_map(ReferenceOr::as_item)
+        .map(|parameter| {
+            let data = parameter.parameter_data_ref();
+            let r#type = match &data.format {
+                ParameterSchemaOrContent::Schema(schema) => {
+                    schema_type(&resolve(&serde_json::to_value(schema).unwrap(), raw))
+                }
+                ParameterSchemaOrContent::Content(_) => "string".to_string(),
+            };
+
+            json!({ "name": data.name, "type": r#type, "value": ORDER_ID })
+        })
+        .collect();
+
+    let mut request = json!({
+        "messageVersion": "1.0",
+        "agent": {
+            "name": "InlineAgent",
+            "id": "INLINE_AGENT",
+            "alias": "INLINE_AGENT_ALIAS",
+            "version": "DRAFT"
+        },
+        "inputText": "Hi, I'm Nancy. May I get a vanilla ice cream, please?",
+        "sessionId": "session-id",
+        "actionGroup": "Waiter",
+        "apiPath": path,
+        "httpMethod": method.to_uppercase(),
+        "parameters": parameters,
+        "sessionAttributes": {},
+        "promptSessionAttributes": {}
+    });
+
+    if let Some(request_body) = operation
+        .request_body
+        .as_ref()
+        .and_then(ReferenceOr::as_item)
+    {
+        let content = request_body
+            .content
+            .iter()
+            .map(|(content_type, media_type)| {
+                let schema = media_type
+                    .schema
+                    .as_ref()
+                    .map(|schema| resolve(&serde_json::to_value(schema).unwrap(), raw))
+                    .unwrap_or(Value::Null);
+                let properties: Vec<Value> = schema["properties"]
+                    .as_object()
+                    .into_iter()
+                    .flatten()
+                    .map(|(name, property)| {
+                        let property = resolve(property, raw);
+
+                        json!({
+                            "name": name,
+                            "type": schema_type(&property),
+                            "value": sample_value(&property),
+                        })
+                    })
+                    .collect();
+
+                (content_type.clone(), json!({ "properties": properties }))
+            })
+            .collect::<serde_json::Map<_, _>>();
+
+        request["requestBody"] = json!({ "content": content });
+    }
+
+    serde_json::from_value(request).expect("valid agent request")
+}
+
+fn resolve(schema: &Value, raw: &Value) -> Value {
+    match schema["$ref"].as_str() {
+        Some(reference) => {
+            let pointer = reference.trim_start_matches('#');
+            resolve(raw.pointer(pointer).expect("reference exists"), raw)
+        }
+        None => schema.clone(),
+    }
+}
+
+fn schema_type(schema: &Value) -> String {
+    schema["type"].as_str().unwrap_or("string").to_string()
+}
+
+fn sample_value(schema: &Value) -> String {
+    if let Some(example) = schema.get("example") {
+        return match example {
+            Value::String(example) => example.clone(),
+            example => example.to_string(),
+        };
+    }
+
+    match schema["type"].as_str() {
+        Some("integer") => "1".to_string(),
+        Some("number") => "1.5".to_string(),
+        Some("boolean") => "true".to_string(),
+        Some("array") => format!("[{FLAVOR}]"),
+        _ => FLAVOR.to_string(),
+    }
+}
+
+/// Validates `value` against the subset of schema objects the waiter uses.
+fn validate(schema: &Value, value: &Value, raw: &Value, at: &str, errors: &mut Vec<String>) {
+    let schema = resolve(schema, raw);
+
+    if value.is_null() && schema["nullable"] == json!(true) {
+        return;
+    }
+
+    let matches_type = match schema["type"].as_str() {
+        Some("object") => value.is_object(),
+        Some("array") => value.is_array(),
+        Some("string") => value.is_string(),
+        Some("integer") => value.is_i64() || value.is_u64(),
+        Some("number") => value.is_number(),
+        Some("boolean") => value.is_boolean(),
+        _ => true,
+    };
+    if !matches_type {
+        errors.push(format!("{at}: expected {} but got {value}", schema["type"]));
+        return;
+    }
+
+    if let Some(allowed) = schema["enum"].as_array() {
+        if !allowed.contains(value) {
+            errors.push(format!("{at}: {value} is not one of {}", schema["enum"]));
+        }
+    }
+
+    for required in schema["required"].as_array().into_iter().flatten() {
+        let name = required.as_str().unwrap_or_default();
+        if value.get(name).is_none() {
+            errors.push(format!("{at}: missing required property {name:?}"));
+        }
+    }
+
+    for (name, property) in schema["properties"].as_object().into_iter().flatten() {
+        if let Some(property_value) = value.get(name) {
+            validate(
+                property,
+                property_value,
+                raw,
+                &format!("{at}.{name}"),
+                errors,
+            );
+        }
+    }
+
+    if let (Some(items), Some(values)) = (schema.get("items"), value.as_array()) {
+        for (index, item) in values.iter().enumerate() {
+            validate(items, item, raw, &format!("{at}[{index}]"), errors);
+        }
+    }
+}
+
+/// Calls the waiter like the agent would.
+async fn call(request: AgentApiRequest) -> ApiResponse {
+    let repository = seeded_repository().await;
+
+    handler(
+        LambdaEvent::new(request, Context::default()),
+        &routes::router(),
+        &repository,
+    )
+    .await
+    .expect("handler succeeds")
+    .response
+}
+
+/// Checks the status code of the response is declared for the operation
+/// and its body validates against the declared schema.
+fn check_response(
+    operation_name: &str,
+    operation: &Operation,
+    response: &ApiResponse,
+    raw: &Value,
+    failures: &mut Vec<String>,
+) {
+    let status_code = response.http_status_code;
+    let Some(declared_response) = operation
+        .responses
+        .responses
+        .get(&StatusCode::Code(status_code as u16))
+        .and_then(ReferenceOr::as_item)
+    else {
+        failures.push(format!(
+            "{operation_name}: responded {status_code} which isn't declared, body: {:?}",
+            response.response_body.content
+        ));
+        return;
+    };
+
+    for (content_type, media_type) in &declared_response.content {
+        let Some(content) = response.response_body.content.get(content_type) else {
+            failures.push(format!("{operation_name}: missing {content_type} response"));
+            continue;
+        };
+        let Ok(body) = serde_json::from_str::<Value>(&content.body) else {
+            failures.push(format!(
+                "{operation_name}: body is not JSON: {}",
+                content.body
+            ));
+            continue;
+        };
+        if let Some(schema) = &media_type.schema {
+            validate(
+                &serde_json::to_value(schema).unwrap(),
+                &body,
+                raw,
+                &format!("{operation_name} {status_code} body"),
+                failures,
+            );
+        }
+    }
+}
+
+fn operation_name(path: &str, method: &str, operation: &Operation) -> String {
+    operation
+        .operation_id
+        .clone()
+        .unwrap_or_else(|| format!("{} {path}", method.to_uppercase()))
+}
+
+/// Sends every operation the request made up by `break_request`, which has to fail with
+/// `error_code` and a response declared for the operation.
+async fn assert_fails_as_declared(
+    break_request: impl Fn(&mut AgentApiRequest),
+    http_status_code: i32,
+    error_code: &str,
+) {
+    let (openapi, raw) = document();
+    let mut failures = Vec::new();
+
+    for (path, method, operation) in openapi.operations() {
+        let operation_name = operation_name(path, method, operation);
+        let mut request = agent_request(path, method, operation, &raw);
+        break_request(&mut request);
+
+        let response = call(request).await;
+
+        if response.http_status_code != http_status_code {
+            failures.push(format!(
+                "{operation_name}: responded {} instead of {http_status_code}",
+                response.http_status_code
+            ));
+        }
+        let body: Value =
+            serde_json::from_str(&response.response_body.content["application/json"].body)
+                .unwrap_or_default();
+        if body["errorCode"] != error_code {
+            failures.push(format!(
+                "{operation_name}: error code {} instead of {error_code}",
+                body["errorCode"]
+            ));
+        }
+        check_response(&operation_name, operation, &response, &raw, &mut failures);
+    }
+
+    assert!(
+        failures.is_empty(),
+        "Waiter doesn't match schemas/waiter.yaml:\n{}",
+        failures.join("\n")
+    );
+}
+
+#[tokio::test]
+async fn implements_every_operation_as_declared() {
+    let (openapi, raw) = document();
+    let mut failures = Vec::new();
+
+    for (path, method, operation) in openapi.operations() {
+        let operation_name = operation_name(path, method, operation);
+        let response = call(agent_request(path, method, operation, &raw)).await;
+
+        check_response(&operation_name, operation, &response, &raw, &mut failures);
+    }
+
+    assert!(
+        failures.is_empty(),
+        "Waiter doesn't match schemas/waiter.yaml:\n{}",
+        failures.join("\n")
+    );
+}
+
+#[tokio::test]
+async fn unknown_paths_are_not_found() {
+    assert_fails_as_declared(
+        |request| request.api_path = format!("{}/unknown", request.api_path),
+        404,
+        "ROUTE_NOT_FOUND",
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn undeclared_methods_are_not_allowed() {
+    // Every waiter operation is a POST.
+    assert_fails_as_declared(
+        |request| request.http_method = "DELETE".to_string(),
+        405,
+        "METHOD_NOT_ALLOWED",
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn invalid_request_bodies_are_bad_requests() {
+    assert_fails_as_declared(
+        |request| {
+            for content in request
+                .request_body
+                .iter_mut()
+                .flat_map(|request_body| request_body.content.values_mut())
+            {
+                content.properties.clear();
+            }
+        },
+        400,
+        "INVALID_REQUEST_BODY",
+    )
+    .await;
+}
+
+#[test]
+fn every_route_is_declared() {
+    let (openapi, _) = document();
+    let declared: Vec<(String, String)> = openapi
+        .operations()
+        .map(|(path, method, _)| (method.to_uppercase(), path.to_string()))
+        .collect();
+
+    let undeclared: Vec<String> = routes::router::<InMemoryOrderRepository>()
+        .routes()
+        .filter(|(method, path)| !declared.contains(&(method.to_uppercase(), path.to_string())))
+        .map(|(method, path)| format!("{method} {path}"))
+        .collect();
+
+    assert!(
+        undeclared.is_empty(),
+        "Routes missing from schemas/waiter.yaml: {undeclared:?}"
+    );
+}
+
+#[test]
+fn schema_file_is_up_to_date() {
+    assert!(
+        WAITER_SCHEMA == routes::openapi_yaml(),
+        "schemas/waiter.yaml is outdated, regenerate it with \
+        `cargo run -p waiter --features schema --bin waiter-openapi > waiter/schemas/waiter.yaml`"
+    );
+}
@@ -0,0 +1,104 @@
+use bedrock_agent_events::api::AgentApiRequest;
+use chrono::Utc;
+use lambda_runtime::{Context, LambdaEvent};
+use serde_json::Value;
+use waiter::{
+    handler,
+    order::Order,
+    repository::{InMemoryOrderRepository, OrderRepository},
+    routes,
+};
+
+const ORDER_ID: &str = "9f0c5a3e-3a8e-4b0e-9a53-0a6d3c1d2b7f";
+
+fn fixture(name: &str) -> AgentApiRequest {
+    let path = format!("{}/tests/fixtures/{name}", env!("CARGO_MANIFEST_DIR"));
+    let content = std::fs::read_to_string(path).expect("fixture exists");
+
+    serde_json::from_str(&content).expect("valid agent request")
+}
+
+/// Repository with Nancy's order containing one chocolate ice cream.
+async fn repository_with_order() -> InMemoryOrderRepository {
+    let repository = InMemoryOrderRepository::new();
+    let mut order = Order::new("Nancy", Utc::now());
+    order.order_id = ORDER_ID.to_string();
+    order.add_ice_cream("Chocolate", Utc::now());
+    repository.create(&order).await.unwrap();
+
+    repository
+}
+
+async fn call(repository: &InMemoryOrderRepository, request: AgentApiRequest) -> (i32, Value) {
+    let response = handler(
+        LambdaEvent::new(request, Context::default()),
+        &routes::router(),
+        repository,
+    )
+    .await
+    .expect("handler succeeds");
+    let body = &response.response.response_body.content["application/json"].body;
+
+    (
+        response.response.http_status_code,
+        serde_json::from_str(body).expect("JSON body"),
+    )
+}
+
+#[tokio::test]
+async fn uses_order_id_path_parameter() {
+    let repository = repository_with_order().await;
+
+    let (status, body) = call(&repository, fixture("add-ice-cream.json")).await;
+
+    assert_eq!(status, 201);
+    assert_eq!(
+        body["message"],
+        format!("Ice cream of Vanilla added to order {ORDER_ID}")
+    );
+    let order = repository.get(ORDER_ID).await.unwrap().unwrap();
+    assert_eq!(order.total_ice_creams(), 2);
+}
+
+#[tokio::test]
+async fn falls_back_to_order_id_in_api_path() {
+    let repository = repository_with_order().await;
+
+    let (status, body) = call(&repository, fixture("remove-ice-cream.json")).await;
+
+    assert_eq!(status, 200);
+    assert_eq!(
+        body["message"],
+        format!("Ice cream of Chocolate removed from order {ORDER_ID}")
+    );
+    let order = repository.get(ORDER_ID).await.unwrap().unwrap();
+    assert!(order.items.is_empty());
+}
+
+#[tokio::test]
+async fn unknown_order_is_not_found() {
+    let repository = InMemoryOrderRepository::new();
+
+    for request in [
+        fixture("add-ice-cream.json"),
+        fixture("remove-ice-cream.json"),
+    ] {
+        let (status, body) = call(&repository, request).await;
+
+        assert_eq!(status, 404);
+        assert_eq!(body["errorCode"], "ORDER_NOT_FOUND");
+        assert_eq!(body["message"], format!("Order {ORDER_ID} not found."));
+    }
+}
+
+#[tokio::test]
+async fn unresolved_order_id_template_is_a_bad_request() {
+    let repository = repository_with_order().await;
+    let mut request = fixture("add-ice-cream.json");
+    request.parameters.clear();
+
+    let (status, body) = call(&repository, request).await;
+
+    assert_eq!(status, 400);
+    assert_eq!(body["errorCode"], "MISSING_PARAMETER");
+}
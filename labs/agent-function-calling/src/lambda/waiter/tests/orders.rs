@@ -0,0 +1,270 @@
+use bedrock_agent_events::api::{AgentApiRequest, AgentApiResponse};
+use lambda_runtime::{Context, LambdaEvent};
+use serde_json::{json, Value};
+use waiter::{
+    handler,
+    order::OrderStatus,
+    repository::{
+        DynamoDbOrderRepository, InMemoryOrderRepository, OrderRepository, RepositoryError,
+    },
+    routes,
+};
+
+fn agent_request(api_path: &str, order_id: Option<&str>, properties: Value) -> AgentApiRequest {
+    let parameters = match order_id {
+        Some(order_id) => json!([{ "name": "orderId", "type": "string", "value": order_id }]),
+        None => json!([]),
+    };
+
+    serde_json::from_value(json!({
+        "messageVersion": "1.0",
+        "agent": {
+            "name": "InlineAgent",
+            "id": "INLINE_AGENT",
+            "alias": "INLINE_AGENT_ALIAS",
+            "version": "DRAFT"
+        },
+        "inputText": "Hi, I'm Nancy. May I get a vanilla ice cream, please?",
+        "sessionId": "session-id",
+        "actionGroup": "Waiter",
+        "apiPath": api_path,
+        "httpMethod": "POST",
+        "parameters": parameters,
+        "sessionAttributes": {},
+        "promptSessionAttributes": {},
+        "requestBody": {
+            "content": {
+                "application/json": {
+                    "properties": properties
+                }
+            }
+        }
+    }))
+    .expect("valid agent request")
+}
+
+async fn call<R: OrderRepository + Sync>(repository: &R, request: AgentApiRequest) -> (i32, Value) {
+    let response: AgentApiResponse = handler(
+        LambdaEvent::new(request, Context::default()),
+        &routes::router(),
+        repository,
+    )
+    .await
+    .expect("handler succeeds");
+    let body = &response.response.response_body.content["application/json"].body;
+
+    (
+        response.response.http_status_code,
+        serde_json::from_str(body).expect("JSON body"),
+    )
+}
+
+/// Starts an order for Nancy, adds two vanilla and one chocolate, then removes one vanilla.
+async fn take_order<R: OrderRepository + Sync>(repository: &R) -> String {
+    let (status, body) = call(
+        repository,
+        agent_request(
+            "/start-order",
+            None,
+            json!([{ "name": "client_name", "type": "string", "value": "Nancy" }]),
+        ),
+    )
+    .await;
+    assert_eq!(status, 201);
+    let order_id = body["orderId"].as_str().expect("orderId").to_string();
+
+    for flavor in ["Vanilla", "vanilla", "Chocolate"] {
+        let (status, _) = call(
+            repository,
+            agent_request(
+                "/add-ice-cream/{orderId}",
+                Some(&order_id),
+                json!([{ "name": "flavor", "type": "string", "value": flavor }]),
+            ),
+        )
+        .await;
+        assert_eq!(status, 201);
+    }
+
+    let (status, _) = call(
+        repository,
+        agent_request(
+            "/remove-ice-cream/{orderId}",
+            Some(&order_id),
+            json!([{ "name": "flavor", "type": "string", "value": "Vanilla" }]),
+        ),
+    )
+    .await;
+    assert_eq!(status, 200);
+
+    order_id
+}
+
+async fn assert_order_taken<R: OrderRepository + Sync>(repository: &R, order_id: &str) {
+    let order = repository
+        .get(order_id)
+        .await
+        .unwrap()
+        .expect("order is stored");
+
+    assert_eq!(order.client_name, "Nancy");
+    assert_eq!(order.status, OrderStatus::Open);
+    assert_eq!(order.total_ice_creams(), 2);
+    assert_eq!(order.items[0].flavor, "Vanilla");
+    assert_eq!(order.items[0].quantity, 1);
+    assert_eq!(order.items[1].flavor, "Chocolate");
+    assert!(order.updated_at >= order.created_at);
+    // Three ice creams added and one removed.
+    assert_eq!(order.version, 4);
+}
+
+/// Two requests read the same order, only the first one to store its change succeeds.
+async fn assert_concurrent_update_conflicts<R: OrderRepository + Sync>(
+    repository: &R,
+    order_id: &str,
+) {
+    let mut first = repository.get(order_id).await.unwrap().unwrap();
+    let mut second = first.clone();
+
+    first.add_ice_cream("Strawberry", chrono::Utc::now());
+    repository.update(&first).await.unwrap();
+    second.add_ice_cream("Pistachio", chrono::Utc::now());
+    let error = repository.update(&second).await.unwrap_err();
+
+    assert!(
+        matches!(&error, RepositoryError::Conflict(id) if id == order_id),
+        "{error:?}"
+    );
+    let order = repository.get(order_id).await.unwrap().unwrap();
+    assert_eq!(order.version, first.version + 1);
+    assert_eq!(order.items.last().unwrap().flavor, "Strawberry");
+}
+
+#[tokio::test]
+async fn persists_orders_in_memory() {
+    let repository = InMemoryOrderRepository::new();
+
+    let order_id = take_order(&repository).await;
+
+    assert_order_taken(&repository, &order_id).await;
+}
+
+#[tokio::test]
+async fn rejects_concurrent_updates_in_memory() {
+    let repository = InMemoryOrderRepository::new();
+    let order_id = take_order(&repository).await;
+
+    assert_concurrent_update_conflicts(&repository, &order_id).await;
+}
+
+#[tokio::test]
+async fn updating_missing_order_is_not_found() {
+    let repository = InMemoryOrderRepository::new();
+    let order = waiter::order::Order::new("Nancy", chrono::Utc::now());
+
+    let error = repository.update(&order).await.unwrap_err();
+
+    assert!(matches!(error, RepositoryError::NotFound(_)), "{error:?}");
+}
+
+#[tokio::test]
+async fn removing_missing_flavor_is_not_found() {
+    let repository = InMemoryOrderRepository::new();
+    let order_id = take_order(&repository).await;
+
+    let (status, body) = call(
+        &repository,
+        agent_request(
+            "/remove-ice-cream/{orderId}",
+            Some(&order_id),
+            json!([{ "name": "flavor", "type": "string", "value": "Cookie Dough" }]),
+        ),
+    )
+    .await;
+
+    assert_eq!(status, 404);
+    assert_eq!(body["errorCode"], "FLAVOR_NOT_IN_ORDER");
+    assert!(body["message"].as_str().unwrap().contains("Cookie Dough"));
+    assert_order_taken(&repository, &order_id).await;
+}
+
+/// Runs against DynamoDB Local, e.g.:
+/// `docker run -p 8000:8000 amazon/dynamodb-local` and
+/// `DYNAMODB_ENDPOINT_URL=http://localhost:8000 cargo test -- --ignored`
+#[tokio::test]
+#[ignore = "requires DynamoDB Local, see DYNAMODB_ENDPOINT_URL"]
+async fn persists_orders_in_dynamodb_local() {
+    use aws_sdk_dynamodb::types::{
+        AttributeDefinition, BillingMode, KeySchemaElement, KeyType, ScalarAttributeType,
+    };
+
+    let endpoint_url =
+        std::env::var("DYNAMODB_ENDPOINT_URL").unwrap_or("http://localhost:8000".to_string());
+    let sdk_config = aws_config::defaults(aws_config::BehaviorVersion::latest())
+        .endpoint_url(endpoint_url)
+        .region(aws_config::Region::new("us-east-1"))
+        .credentials_provider(aws_sdk_dynamodb::config::Credentials::for_tests())
+        .load()
+        .await;
+    let client = aws_sdk_dynamodb::Client::new(&sdk_config);
+    let table_name = format!("orders-{}", uuid::Uuid::new_v4());
+
+    client
+        .create_table()
+        .table_name(&table_name)
+        .attribute_definitions(
+            AttributeDefinition::builder()
+                .attribute_name("order_id")
+                .attribute_type(ScalarAttributeType::S)
+                .build()
+                .unwrap(),
+        )
+        .key_schema(
+            KeySchemaElement::builder()
+                .attribute_name("order_id")
+                .key_type(KeyType::Hash)
+                .build()
+                .unwrap(),
+        )
+        .billing_mode(BillingMode::PayPerRequest)
+        .send()
+        .await
+        .expect("table created");
+
+    let repository = DynamoDbOrderRepository::new(client.clone(), &table_name);
+    let order_id = take_order(&repository).await;
+
+    assert_order_taken(&repository, &order_id).await;
+    assert_concurrent_update_conflicts(&repository, &order_id).await;
+
+    client
+        .delete_table()
+        .table_name(&table_name)
+        .send()
+        .await
+        .expect("table deleted");
+}
+
+#[tokio::test]
+async fn missing_flavor_is_a_bad_request() {
+    let repository = InMemoryOrderRepository::new();
+    let order_id = take_order(&repository).await;
+
+    let (status, body) = call(
+        &repository,
+        agent_request(
+            "/add-ice-cream/{orderId}",
+            Some(&order_id),
+            json!([{ "name": "flavour", "type": "string", "value": "Vanilla" }]),
+        ),
+    )
+    .await;
+
+    assert_eq!(status, 400);
+    assert_eq!(body["errorCode"], "INVALID_REQUEST_BODY");
+    assert_eq!(
+        body["message"],
+        "Invalid request body: missing field `flavor`."
+    );
+    assert_order_taken(&repository, &order_id).await;
+}
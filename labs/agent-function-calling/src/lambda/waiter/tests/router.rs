@@ -0,0 +1,156 @@
+use bedrock_agent_events::api::AgentApiRequest;
+use lambda_runtime::Error;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use waiter::{
+    error::ApiError,
+    openapi::Operation,
+    router::{RouteRequest, Router},
+};
+
+fn agent_request(http_method: &str, api_path: &str) -> AgentApiRequest {
+    serde_json::from_value(json!({
+        "messageVersion": "1.0",
+        "agent": {
+            "name": "InlineAgent",
+            "id": "INLINE_AGENT",
+            "alias": "INLINE_AGENT_ALIAS",
+            "version": "DRAFT"
+        },
+        "inputText": "How many ice creams are in my order?",
+        "sessionId": "session-id",
+        "actionGroup": "Waiter",
+        "apiPath": api_path,
+        "httpMethod": http_method,
+        "parameters": []
+    }))
+    .unwrap()
+}
+
+async fn order_summary(
+    request: &RouteRequest<'_>,
+    _: (),
+    calls: &AtomicUsize,
+) -> Result<Value, Error> {
+    calls.fetch_add(1, Ordering::SeqCst);
+    let order_id = request.path_param("orderId")?;
+    let flavor = request.path_param("flavor").ok();
+
+    Ok(json!({ "orderId": order_id, "flavor": flavor }))
+}
+
+fn router() -> Router<AtomicUsize> {
+    Router::new()
+        .route(
+            Operation::get("/orders/{orderId}", "GetOrder"),
+            |request, body, calls| Box::pin(order_summary(request, body, calls)),
+        )
+        .route(
+            Operation::get("/orders/{orderId}/flavors/{flavor}", "GetOrderFlavor"),
+            |request, body, calls| Box::pin(order_summary(request, body, calls)),
+        )
+        .route(
+            Operation::delete("/orders/{orderId}", "CancelOrder").responds(202, "Cancelling."),
+            |request, body, calls| Box::pin(order_summary(request, body, calls)),
+        )
+}
+
+async fn dispatch_error(request: AgentApiRequest) -> ApiError {
+    let error = router()
+        .dispatch(&request, &AtomicUsize::new(0))
+        .await
+        .unwrap_err();
+
+    *error.downcast::<ApiError>().expect("an ApiError")
+}
+
+#[tokio::test]
+async fn extracts_path_params() {
+    let calls = AtomicUsize::new(0);
+
+    let reply = router()
+        .dispatch(&agent_request("GET", "/orders/42/flavors/Vanilla"), &calls)
+        .await
+        .unwrap();
+
+    assert_eq!(reply.http_status_code, 200);
+    assert_eq!(reply.body, json!({ "orderId": "42", "flavor": "Vanilla" }));
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn prefers_parameters_over_unresolved_template() {
+    let mut request = agent_request("get", "/orders/{orderId}");
+    request.parameters = serde_json::from_value(json!([
+        { "name": "orderId", "type": "string", "value": "42" }
+    ]))
+    .unwrap();
+
+    let reply = router()
+        .dispatch(&request, &AtomicUsize::new(0))
+        .await
+        .unwrap();
+
+    assert_eq!(reply.body, json!({ "orderId": "42", "flavor": null }));
+}
+
+#[tokio::test]
+async fn unknown_path_is_not_found() {
+    let error = dispatch_error(agent_request("GET", "/orders/42/toppings")).await;
+
+    assert_eq!(error.http_status_code(), 404);
+    assert_eq!(error.error_code(), "ROUTE_NOT_FOUND");
+}
+
+#[tokio::test]
+async fn unknown_method_is_not_allowed() {
+    let error = dispatch_error(agent_request("POST", "/orders/42")).await;
+
+    assert_eq!(error.http_status_code(), 405);
+    assert_eq!(error.error_code(), "METHOD_NOT_ALLOWED");
+    assert_eq!(
+        error.to_string(),
+        "Method POST is not allowed for /orders/42, use GET or DELETE."
+    );
+}
+
+#[tokio::test]
+async fn replies_with_declared_status_code() {
+    let reply = router()
+        .dispatch(&agent_request("DELETE", "/orders/42"), &AtomicUsize::new(0))
+        .await
+        .unwrap();
+
+    assert_eq!(reply.http_status_code, 202);
+}
+
+#[test]
+fn lists_routes() {
+    let routes: Vec<_> = router().routes().collect();
+
+    assert_eq!(
+        routes,
+        vec![
+            ("GET", "/orders/{orderId}"),
+            ("GET", "/orders/{orderId}/flavors/{flavor}"),
+            ("DELETE", "/orders/{orderId}"),
+        ]
+    );
+}
+
+#[test]
+fn derives_path_params_from_template() {
+    let operation = Operation::get("/orders/{orderId}/flavors/{flavor}", "GetOrderFlavor");
+
+    assert_eq!(
+        operation.path_params().collect::<Vec<_>>(),
+        vec!["orderId", "flavor"]
+    );
+}
+
+#[test]
+#[should_panic(expected = "has no parameter flavor")]
+fn rejects_description_of_unknown_path_param() {
+    Operation::get("/orders/{orderId}", "GetOrder")
+        .describe_path_param("flavor", "Flavor of the ice cream.");
+}
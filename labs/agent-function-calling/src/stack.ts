@@ -3,7 +3,7 @@ import * as cdk from 'aws-cdk-lib';
 import * as bedrock from 'aws-cdk-lib/aws-bedrock';
 import * as dynamodb from 'aws-cdk-lib/aws-dynamodb';
 import * as iam from 'aws-cdk-lib/aws-iam';
-import { IFunction } from 'aws-cdk-lib/aws-lambda';
+import { FunctionUrlAuthType, IFunction, InvokeMode } from 'aws-cdk-lib/aws-lambda';
 import { join } from 'node:path';
 
 export class AgentFunctionCallingStack extends cdk.Stack {
@@ -43,6 +43,9 @@ export class AgentFunctionCallingStack extends cdk.Stack {
             .withDynamoDBTable(this._table, 'TABLE_NAME')
             .build();
 
+        // Streams the answers as server-sent events through a function URL, when deployed with STREAM_RESPONSE=true
+        const streamResponse = process.env.STREAM_RESPONSE === 'true';
+
         this._agentCaller = new RustLambdaFunctionBuilder(this, `AgentCallerLambda${id}`, {
             name: 'agent-caller',
             path: join(__dirname, 'lambda', 'agent-caller'),
@@ -51,7 +54,10 @@ export class AgentFunctionCallingStack extends cdk.Stack {
             .withEnvironmentVariable("MODEL_ID", foundationModelId.modelId)
             .withEnvironmentVariable("ICE_CREAM_MAKER_LAMBDA", this._iceCreamMakerLambda.functionArn)
             .withEnvironmentVariable("WAITER_LAMBDA", this._waiterLambda.functionArn)
+            .withEnvironmentVariable("STREAM_RESPONSE", String(streamResponse))
             .withDuration(30)
+            // Action groups set to return control are run by the agent caller itself
+            .withDynamoDBTable(this._table, 'TABLE_NAME')
             .attachInlinePolicy(
                 new iam.Policy(this, `BedrockPolicy${id}`, {
                     statements: [
@@ -87,5 +93,17 @@ export class AgentFunctionCallingStack extends cdk.Stack {
                 })
             )
             .build();
+
+        if (streamResponse) {
+            // Invoked with InvokeWithResponseStream, requests must be signed with SigV4
+            const functionUrl = this._agentCaller.addFunctionUrl({
+                authType: FunctionUrlAuthType.AWS_IAM,
+                invokeMode: InvokeMode.RESPONSE_STREAM,
+            });
+
+            new cdk.CfnOutput(this, `AgentCallerUrl${id}`, {
+                value: functionUrl.url,
+            });
+        }
     }
 }
@@ -0,0 +1,92 @@
+/**
+ * Questions about an uploaded document, or one sent in the question, asked over HTTP. Bedrock keeps
+ * the conversation, so follow-up questions only need the session id of the previous answer.
+ *
+ * Reference: https://docs.aws.amazon.com/bedrock/latest/userguide/knowledge-base-chatdoc.html
+ */
+use crate::{
+    citation::{Citation, CitationFormat},
+    document::S3Document,
+    source::{DocumentSource, InlineDocument},
+};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+
+/// Asks about either a document in S3 or one sent inline.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Question {
+    /// S3 URI of the document, e.g. `s3://<bucket>/<key>`.
+    #[serde(default)]
+    pub document_uri: Option<String>,
+    /// Document sent in the question instead, when it isn't in S3.
+    #[serde(default)]
+    pub document: Option<DocumentBody>,
+    pub question: String,
+    /// Session of the previous answer, to ask a follow-up question.
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Renders the answer with its citations, `footnotes` or `markdown`.
+    #[serde(default)]
+    pub citation_format: Option<CitationFormat>,
+}
+
+/// Document sent in the body of the question.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentBody {
+    /// File name of the document, e.g. `notes.pdf`.
+    pub name: String,
+    /// Guessed from the extension of the name when it isn't given.
+    #[serde(default)]
+    pub content_type: Option<String>,
+    /// Content of the document, in base64.
+    pub data: String,
+}
+
+impl Question {
+    /// The document the question is about, checked before asking the model.
+    pub fn source(&self) -> Result<DocumentSource, String> {
+        match (&self.document_uri, &self.document) {
+            (Some(uri), None) => S3Document::from_uri(uri)
+                .map(DocumentSource::S3)
+                .ok_or_else(|| {
+                    format!("Invalid documentUri {uri:?}, expected s3://<bucket>/<key>.")
+                }),
+            (None, Some(document)) => {
+                let data = BASE64_STANDARD
+                    .decode(document.data.trim())
+                    .map_err(|error| format!("Invalid base64 document data: {error}"))?;
+
+                InlineDocument::new(&document.name, document.content_type.as_deref(), data)
+                    .map(DocumentSource::Inline)
+                    .map_err(|error| format!("{error}."))
+            }
+            (Some(_), Some(_)) => {
+                Err("Expected either a documentUri or a document, not both.".to_string())
+            }
+            (None, None) => Err("Missing documentUri or document.".to_string()),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Answer {
+    pub answer: String,
+    /// Session to send along with the next question about the same document.
+    pub session_id: String,
+    pub citations: Vec<Citation>,
+    /// The answer with its citations, when the question asks for a format.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rendered: Option<String>,
+    /// `INTERVENED` when a guardrail changed the answer of the model.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guardrail_action: Option<String>,
+}
+
+/// Body of the responses to requests that couldn't be answered.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ErrorBody {
+    pub error: String,
+}
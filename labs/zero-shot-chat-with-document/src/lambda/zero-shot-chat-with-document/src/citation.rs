@@ -0,0 +1,191 @@
+/**
+ * Citations of the generated text: which passages of the document support each part of it, and
+ * how to render them along with the text so readers can check them.
+ *
+ * Reference:
+ * - https://docs.aws.amazon.com/bedrock/latest/APIReference/API_agent-runtime_Citation.html
+ * - https://docs.aws.amazon.com/bedrock/latest/APIReference/API_agent-runtime_Span.html
+ */
+use aws_sdk_bedrockagentruntime::types as bedrock;
+use serde::{Deserialize, Serialize};
+
+/// Length of the excerpts of the references in rendered text.
+const EXCERPT_LENGTH: usize = 200;
+
+/// Part of the generated text backed by passages of the document.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Citation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// Where the part is in the generated text.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub span: Option<Span>,
+    pub references: Vec<Reference>,
+}
+
+/// Characters of the generated text a citation covers, both included.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Passage of the document.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Reference {
+    pub text: String,
+    /// S3 URI of the document.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+}
+
+/// How citations are rendered along with the generated text.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CitationFormat {
+    /// `[1]` after each cited part, and the numbered excerpts at the end.
+    Footnotes,
+    /// Markdown footnotes, `[^1]` after each cited part and their definitions at the end.
+    Markdown,
+}
+
+impl CitationFormat {
+    pub fn parse(format: &str) -> Option<Self> {
+        match format {
+            "footnotes" => Some(CitationFormat::Footnotes),
+            "markdown" => Some(CitationFormat::Markdown),
+            _ => None,
+        }
+    }
+
+    fn marker(self, number: usize) -> String {
+        match self {
+            CitationFormat::Footnotes => format!("[{number}]"),
+            CitationFormat::Markdown => format!("[^{number}]"),
+        }
+    }
+
+    fn note(self, number: usize, reference: &Reference) -> String {
+        let excerpt = excerpt(&reference.text);
+        let location = reference
+            .location
+            .as_ref()
+            .map(|location| format!(" ({location})"))
+            .unwrap_or_default();
+
+        match self {
+            CitationFormat::Footnotes => format!("[{number}] \"{excerpt}\"{location}"),
+            CitationFormat::Markdown => format!("[^{number}]: > {excerpt}{location}"),
+        }
+    }
+}
+
+impl Citation {
+    /// Character of the text after the cited part: the end of its span, or of its text when it
+    /// has no span.
+    pub fn end(&self, text: &str) -> Option<usize> {
+        if let Some(span) = self.span {
+            return Some(span.end + 1);
+        }
+
+        let cited = self.text.as_deref().filter(|cited| !cited.is_empty())?;
+        let start = text.find(cited)?;
+
+        Some(text[..start].chars().count() + cited.chars().count())
+    }
+}
+
+/// Renders the text with a marker after every cited part, and the excerpts of the references
+/// numbered in order at the end. Returns the text as is when nothing is cited.
+pub fn render(text: &str, citations: &[Citation], format: CitationFormat) -> String {
+    let length = text.chars().count();
+    let mut notes = Vec::new();
+    let mut markers = Vec::new();
+
+    for citation in citations
+        .iter()
+        .filter(|citation| !citation.references.is_empty())
+    {
+        let mut marker = String::new();
+        for reference in &citation.references {
+            notes.push(format.note(notes.len() + 1, reference));
+            marker.push_str(&format.marker(notes.len()));
+        }
+
+        let end = citation.end(text).unwrap_or(length).min(length);
+        markers.push((end, marker));
+    }
+
+    if notes.is_empty() {
+        return text.to_string();
+    }
+
+    // Stable, so markers at the same place keep the order of their numbers
+    markers.sort_by_key(|(end, _)| *end);
+
+    let mut markers = markers.into_iter().peekable();
+    let mut rendered = String::with_capacity(text.len());
+    for (index, character) in text.chars().enumerate() {
+        while let Some((_, marker)) = markers.next_if(|(end, _)| *end == index) {
+            rendered.push_str(&marker);
+        }
+        rendered.push(character);
+    }
+    for (_, marker) in markers {
+        rendered.push_str(&marker);
+    }
+
+    rendered.push_str("\n\n");
+    rendered.push_str(&notes.join("\n"));
+
+    rendered
+}
+
+/// The passage in a single line, shortened to [`EXCERPT_LENGTH`] characters.
+fn excerpt(passage: &str) -> String {
+    let passage = passage.split_whitespace().collect::<Vec<_>>().join(" ");
+    if passage.chars().count() <= EXCERPT_LENGTH {
+        return passage;
+    }
+
+    let shortened: String = passage.chars().take(EXCERPT_LENGTH).collect();
+    format!("{}…", shortened.trim_end())
+}
+
+impl From<&bedrock::Citation> for Citation {
+    fn from(citation: &bedrock::Citation) -> Self {
+        let text_part = citation
+            .generated_response_part()
+            .and_then(|part| part.text_response_part());
+
+        Citation {
+            text: text_part.and_then(|part| part.text()).map(str::to_string),
+            span: text_part.and_then(|part| part.span()).and_then(|span| {
+                match (span.start(), span.end()) {
+                    (Some(start), Some(end)) if 0 <= start && start <= end => Some(Span {
+                        start: start as usize,
+                        end: end as usize,
+                    }),
+                    _ => None,
+                }
+            }),
+            references: citation
+                .retrieved_references()
+                .iter()
+                .map(|reference| Reference {
+                    text: reference
+                        .content()
+                        .map(|content| content.text.clone())
+                        .unwrap_or_default(),
+                    location: reference
+                        .location()
+                        .and_then(|location| location.s3_location())
+                        .and_then(|s3_location| s3_location.uri())
+                        .map(str::to_string),
+                })
+                .collect(),
+        }
+    }
+}
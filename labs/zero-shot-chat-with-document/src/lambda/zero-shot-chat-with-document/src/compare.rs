@@ -0,0 +1,479 @@
+/**
+ * Questions comparing several documents in S3, asked over HTTP. Bedrock reads a single source per
+ * request, so the question is asked about each document separately, and their answers are then
+ * compared in a last request, with the answers as its only source.
+ *
+ * Sets with more than "MAX_COMPARED_DOCUMENTS" documents are split into chunks of at most that
+ * many, the answers of each chunk are compared first, and then the answers of the chunks.
+ *
+ * Reference:
+ * - https://docs.aws.amazon.com/bedrock/latest/APIReference/API_agent-runtime_ExternalSourcesRetrieveAndGenerateConfiguration.html
+ * - https://docs.aws.amazon.com/AmazonS3/latest/API/API_ListObjectsV2.html
+ */
+use crate::{
+    citation::Citation,
+    config::Config,
+    document::S3Document,
+    source::{retrieve_and_generate_configuration, DocumentSource, InlineDocument},
+    summary::SUMMARY_SUFFIX,
+};
+use aws_sdk_bedrockagentruntime::{error::DisplayErrorContext, types::RetrieveAndGenerateInput};
+use futures::{stream, StreamExt};
+use lambda_runtime::tracing;
+use serde::{Deserialize, Serialize};
+use std::{fmt, slice};
+
+/// Identifier of the document with the answers about every document, or chunk of documents.
+pub const ANSWERS_DOCUMENT: &str = "answers.md";
+
+/// Compares the documents given by one of `documentUris`, `prefix` or `manifestUri`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Comparison {
+    /// S3 URIs of the documents.
+    #[serde(default)]
+    pub document_uris: Vec<String>,
+    /// Every document under an S3 prefix, e.g. `s3://<bucket>/reports/2024/`.
+    #[serde(default)]
+    pub prefix: Option<String>,
+    /// S3 URI of a JSON object with the S3 URIs of the documents, see [`Manifest`].
+    #[serde(default)]
+    pub manifest_uri: Option<String>,
+    pub question: String,
+}
+
+/// Where the documents to compare are listed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DocumentSet {
+    Listed(Vec<S3Document>),
+    Prefix { bucket: String, prefix: String },
+    Manifest(S3Document),
+}
+
+/// Object listing documents to compare, e.g. `{"documents": ["s3://<bucket>/a.pdf", ...]}`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Manifest {
+    pub documents: Vec<String>,
+}
+
+impl Comparison {
+    pub fn document_set(&self) -> Result<DocumentSet, String> {
+        match (
+            !self.document_uris.is_empty(),
+            &self.prefix,
+            &self.manifest_uri,
+        ) {
+            (true, None, None) => parse_uris(&self.document_uris).map(DocumentSet::Listed),
+            (false, Some(prefix), None) => {
+                let (bucket, prefix) = prefix
+                    .strip_prefix("s3://")
+                    .and_then(|prefix| prefix.split_once('/'))
+                    .filter(|(bucket, _)| !bucket.is_empty())
+                    .ok_or_else(|| {
+                        format!("Invalid prefix {prefix:?}, expected s3://<bucket>/<prefix>.")
+                    })?;
+
+                Ok(DocumentSet::Prefix {
+                    bucket: bucket.to_string(),
+                    prefix: prefix.to_string(),
+                })
+            }
+            (false, None, Some(uri)) => S3Document::from_uri(uri)
+                .map(DocumentSet::Manifest)
+                .ok_or_else(|| {
+                    format!("Invalid manifestUri {uri:?}, expected s3://<bucket>/<key>.")
+                }),
+            (false, None, None) => Err("Missing documentUris, prefix or manifestUri.".to_string()),
+            _ => Err("Expected only one of documentUris, prefix or manifestUri.".to_string()),
+        }
+    }
+}
+
+impl Manifest {
+    pub fn from_json(json: &[u8]) -> Result<Vec<S3Document>, String> {
+        let manifest: Manifest =
+            serde_json::from_slice(json).map_err(|error| format!("Invalid manifest: {error}."))?;
+
+        parse_uris(&manifest.documents)
+    }
+}
+
+fn parse_uris(uris: &[String]) -> Result<Vec<S3Document>, String> {
+    uris.iter()
+        .map(|uri| {
+            S3Document::from_uri(uri).ok_or_else(|| {
+                format!("Invalid document URI {uri:?}, expected s3://<bucket>/<key>.")
+            })
+        })
+        .collect()
+}
+
+/// Whether an object listed under a prefix is a document to compare, and not a folder or a
+/// summary.
+pub fn is_comparable(key: &str) -> bool {
+    !key.ends_with('/') && !key.ends_with(SUMMARY_SUFFIX)
+}
+
+/// Removes the documents listed twice, keeping their first position, and checks there are enough
+/// of them to compare.
+pub fn check_documents(documents: Vec<S3Document>) -> Result<Vec<S3Document>, String> {
+    let mut unique: Vec<S3Document> = Vec::with_capacity(documents.len());
+    for document in documents {
+        if !unique.contains(&document) {
+            unique.push(document);
+        }
+    }
+
+    match unique.len() {
+        0 => Err("No documents to compare.".to_string()),
+        1 => Err(format!(
+            "Only {} to compare, expected at least 2 documents.",
+            unique[0].uri()
+        )),
+        _ => Ok(unique),
+    }
+}
+
+/// Splits `items` into as few chunks of at most `max` items as possible, in order, with sizes
+/// differing by one at most so no chunk is left with a single item to compare.
+pub fn chunks<T>(items: &[T], max: usize) -> Vec<&[T]> {
+    if items.is_empty() {
+        return Vec::new();
+    }
+    let count = items.len().div_ceil(max.max(1));
+    let (size, larger) = (items.len() / count, items.len() % count);
+
+    let mut rest = items;
+    (0..count)
+        .map(|index| {
+            let (chunk, tail) = rest.split_at(size + usize::from(index < larger));
+            rest = tail;
+            chunk
+        })
+        .collect()
+}
+
+/// Prompt for one of the documents compared.
+pub fn chunk_prompt(question: &str) -> String {
+    format!(
+        "The given document is one of the documents being compared to answer the question below. \
+        Answer it with what this document says, stating the facts and figures that matter to \
+        compare it with the others.\n\n\
+        Question: {question}"
+    )
+}
+
+/// Prompt comparing the answers about every document, or chunk of documents, given as
+/// [`answers_document`].
+pub fn comparison_prompt(question: &str) -> String {
+    format!(
+        "The given document has what several documents say about the question below, under the \
+        S3 URIs of the documents it comes from. Compare them to answer the question, naming the \
+        documents.\n\nQuestion: {question}"
+    )
+}
+
+/// The answers, in Markdown, each under the URIs of the documents it is about.
+pub fn answers_document<'a>(answers: impl IntoIterator<Item = (&'a [String], &'a str)>) -> String {
+    answers
+        .into_iter()
+        .map(|(documents, answer)| format!("## {}\n\n{}", documents.join(", "), answer.trim()))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Answers the question of the comparison about each of its documents, up to
+/// "MAX_CONCURRENT_DOCUMENTS" at the same time, and then compares their answers, by chunks of
+/// "MAX_COMPARED_DOCUMENTS" first when there are more.
+pub async fn compare(
+    comparison: &Comparison,
+    config: &Config,
+    bedrock_agent_runtime_client: &aws_sdk_bedrockagentruntime::Client,
+    s3_client: &aws_sdk_s3::Client,
+) -> Result<ComparisonAnswer, CompareError> {
+    if comparison.question.trim().is_empty() {
+        return Err(CompareError::Invalid("Missing question.".to_string()));
+    }
+    let document_set = comparison.document_set().map_err(CompareError::Invalid)?;
+    let documents = comparison_documents(document_set, s3_client).await?;
+    let documents = check_documents(documents).map_err(CompareError::Invalid)?;
+    let uris: Vec<String> = documents.iter().map(S3Document::uri).collect();
+
+    tracing::info!({ documents = ?uris }, "Comparison received.");
+
+    // Answers are kept in the order of the documents
+    let prompt = chunk_prompt(&comparison.question);
+    let document_answers = stream::iter(&documents)
+        .map(|document| {
+            let prompt = &prompt;
+
+            async move {
+                let source = DocumentSource::S3(document.clone());
+                let (answer, citations) =
+                    generate(&source, prompt, config, bedrock_agent_runtime_client).await?;
+
+                Ok::<_, CompareError>(DocumentAnswer {
+                    document: document.uri(),
+                    answer,
+                    citations,
+                })
+            }
+        })
+        .buffered(config.max_concurrent_documents)
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let document_chunks = chunks(&document_answers, config.max_compared_documents);
+    let chunk_answers = if document_chunks.len() > 1 {
+        tracing::info!({ chunks = document_chunks.len() }, "Comparing by chunks.");
+
+        stream::iter(document_chunks)
+            .map(|chunk| async move {
+                let documents: Vec<String> =
+                    chunk.iter().map(|answer| answer.document.clone()).collect();
+                let (answer, citations) = compare_answers(
+                    chunk
+                        .iter()
+                        .map(|answer| (slice::from_ref(&answer.document), answer.answer.as_str())),
+                    &comparison.question,
+                    config,
+                    bedrock_agent_runtime_client,
+                )
+                .await?;
+
+                Ok::<_, CompareError>(ChunkAnswer {
+                    documents,
+                    answer,
+                    citations,
+                })
+            })
+            .buffered(config.max_concurrent_documents)
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?
+    } else {
+        Vec::new()
+    };
+
+    let answers: Vec<(&[String], &str)> = if chunk_answers.is_empty() {
+        document_answers
+            .iter()
+            .map(|answer| (slice::from_ref(&answer.document), answer.answer.as_str()))
+            .collect()
+    } else {
+        chunk_answers
+            .iter()
+            .map(|answer| (answer.documents.as_slice(), answer.answer.as_str()))
+            .collect()
+    };
+    let (answer, citations) = compare_answers(
+        answers,
+        &comparison.question,
+        config,
+        bedrock_agent_runtime_client,
+    )
+    .await?;
+
+    Ok(ComparisonAnswer {
+        answer,
+        documents: uris,
+        citations,
+        document_answers,
+        chunk_answers,
+    })
+}
+
+/// Compares the answers about several documents, or chunks of documents.
+async fn compare_answers<'a>(
+    answers: impl IntoIterator<Item = (&'a [String], &'a str)>,
+    question: &str,
+    config: &Config,
+    bedrock_agent_runtime_client: &aws_sdk_bedrockagentruntime::Client,
+) -> Result<(String, Vec<Citation>), CompareError> {
+    let answers = InlineDocument::new(
+        ANSWERS_DOCUMENT,
+        None,
+        answers_document(answers).into_bytes(),
+    )
+    .map_err(|error| {
+        CompareError::Failed(format!(
+            "Couldn't compare the answers about each document: {error}"
+        ))
+    })?;
+
+    generate(
+        &DocumentSource::Inline(answers),
+        &comparison_prompt(question),
+        config,
+        bedrock_agent_runtime_client,
+    )
+    .await
+}
+
+/// The documents of a comparison, as listed in the request, under its prefix or in its manifest.
+async fn comparison_documents(
+    document_set: DocumentSet,
+    s3_client: &aws_sdk_s3::Client,
+) -> Result<Vec<S3Document>, CompareError> {
+    match document_set {
+        DocumentSet::Listed(documents) => Ok(documents),
+        DocumentSet::Prefix { bucket, prefix } => {
+            let mut documents = Vec::new();
+            let mut pages = s3_client
+                .list_objects_v2()
+                .bucket(&bucket)
+                .prefix(&prefix)
+                .into_paginator()
+                .send();
+            while let Some(page) = pages.next().await {
+                let page = page.map_err(|error| {
+                    CompareError::Failed(format!(
+                        "Couldn't list the documents under s3://{bucket}/{prefix}: {}",
+                        DisplayErrorContext(&error)
+                    ))
+                })?;
+                documents.extend(
+                    page.contents()
+                        .iter()
+                        .filter_map(|object| object.key())
+                        .filter(|key| is_comparable(key))
+                        .map(|key| S3Document {
+                            bucket: bucket.clone(),
+                            key: key.to_string(),
+                        }),
+                );
+            }
+
+            Ok(documents)
+        }
+        DocumentSet::Manifest(manifest) => {
+            let object = s3_client
+                .get_object()
+                .bucket(&manifest.bucket)
+                .key(&manifest.key)
+                .send()
+                .await
+                .map_err(|error| match error.as_service_error() {
+                    Some(service_error) if service_error.is_no_such_key() => {
+                        CompareError::Invalid(format!("Manifest {} not found.", manifest.uri()))
+                    }
+                    _ => CompareError::Failed(format!(
+                        "Couldn't read manifest {}: {}",
+                        manifest.uri(),
+                        DisplayErrorContext(&error)
+                    )),
+                })?;
+            let json = object.body.collect().await.map_err(|error| {
+                CompareError::Failed(format!(
+                    "Couldn't read manifest {}: {error}",
+                    manifest.uri()
+                ))
+            })?;
+
+            Manifest::from_json(&json.into_bytes()).map_err(CompareError::Invalid)
+        }
+    }
+}
+
+/// Asks the model about the source in a new session, returning its answer and citations.
+async fn generate(
+    source: &DocumentSource,
+    prompt: &str,
+    config: &Config,
+    bedrock_agent_runtime_client: &aws_sdk_bedrockagentruntime::Client,
+) -> Result<(String, Vec<Citation>), CompareError> {
+    let input = RetrieveAndGenerateInput::builder()
+        .text(prompt)
+        .build()
+        .map_err(|error| CompareError::Failed(error.to_string()))?;
+    let configuration =
+        retrieve_and_generate_configuration(source, &config.generation, &config.model_arn)
+            .map_err(|error| CompareError::Failed(error.to_string()))?;
+
+    let response = bedrock_agent_runtime_client
+        .retrieve_and_generate()
+        .input(input)
+        .retrieve_and_generate_configuration(configuration)
+        .send()
+        .await
+        .map_err(|error| {
+            let message = format!(
+                "Couldn't answer about {source}: {}",
+                DisplayErrorContext(&error)
+            );
+            // Documents the model can't read
+            if error
+                .as_service_error()
+                .is_some_and(|error| error.is_validation_exception())
+            {
+                CompareError::Invalid(message)
+            } else {
+                CompareError::Failed(message)
+            }
+        })?;
+
+    Ok((
+        response
+            .output()
+            .map(|output| output.text().to_string())
+            .unwrap_or_default(),
+        response.citations().iter().map(Citation::from).collect(),
+    ))
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ComparisonAnswer {
+    pub answer: String,
+    /// S3 URIs of the documents compared.
+    pub documents: Vec<String>,
+    /// Citations of the answer, which point to the answers about each document, or chunk of
+    /// documents, with their own.
+    pub citations: Vec<Citation>,
+    /// Answers about each document.
+    pub document_answers: Vec<DocumentAnswer>,
+    /// Comparisons of each chunk of documents, when there were more than "MAX_COMPARED_DOCUMENTS".
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub chunk_answers: Vec<ChunkAnswer>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentAnswer {
+    /// S3 URI of the document asked about.
+    pub document: String,
+    pub answer: String,
+    pub citations: Vec<Citation>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkAnswer {
+    /// S3 URIs of the documents compared in the chunk.
+    pub documents: Vec<String>,
+    pub answer: String,
+    pub citations: Vec<Citation>,
+}
+
+/// Why a comparison couldn't be answered.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompareError {
+    /// The request can't be answered as is, e.g. its manifest doesn't exist or the model can't
+    /// read one of its documents.
+    Invalid(String),
+    /// Reading the documents or asking the model about them failed.
+    Failed(String),
+}
+
+impl fmt::Display for CompareError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompareError::Invalid(error) | CompareError::Failed(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for CompareError {}
@@ -0,0 +1,181 @@
+use crate::{
+    citation::CitationFormat,
+    generation::GenerationSettings,
+    task::{TaskCatalog, DEFAULT_TASKS},
+};
+use std::fmt;
+
+pub const DEFAULT_MAX_CONCURRENT_DOCUMENTS: usize = 4;
+pub const DEFAULT_MAX_COMPARED_DOCUMENTS: usize = 20;
+
+/// Configuration of the zero-shot chat with document lambda function, read once on cold start.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    /// ARN of the foundation model that answers about the document.
+    pub model_arn: String,
+    /// How many documents are summarized at the same time, "MAX_CONCURRENT_DOCUMENTS" is
+    /// [`DEFAULT_MAX_CONCURRENT_DOCUMENTS`] when it isn't set.
+    pub max_concurrent_documents: usize,
+    /// How many documents are compared at once, larger sets are compared by chunks of at most
+    /// this many. "MAX_COMPARED_DOCUMENTS" is [`DEFAULT_MAX_COMPARED_DOCUMENTS`] when it isn't set.
+    pub max_compared_documents: usize,
+    pub sink: SinkConfig,
+    /// Taken from the "TASKS" environment variable, [`DEFAULT_TASKS`] when it isn't set.
+    pub tasks: TaskCatalog,
+    /// How citations are rendered in the stored summaries, "CITATION_FORMAT" is `footnotes` or
+    /// `markdown`. They aren't rendered when it isn't set.
+    pub citation_format: Option<CitationFormat>,
+    /// Taken from the "GENERATION" environment variable, the model defaults when it isn't set.
+    /// Tasks can set their own.
+    pub generation: GenerationSettings,
+}
+
+/// Where summaries are stored, chosen with "SUMMARY_SINK".
+#[derive(Debug, Clone, PartialEq)]
+pub enum SinkConfig {
+    /// `s3`, the default: next to the document, or in "SUMMARY_BUCKET" when it is set.
+    S3 { bucket: Option<String> },
+    /// `dynamodb`: in the "SUMMARY_TABLE" table.
+    DynamoDb { table_name: String },
+}
+
+impl Config {
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Reads the configuration from the given variables.
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self, ConfigError> {
+        let mut errors = Vec::new();
+        let model_arn = required(&var, "MODEL_ARN", &mut errors);
+        let max_concurrent_documents = positive(
+            &var,
+            "MAX_CONCURRENT_DOCUMENTS",
+            DEFAULT_MAX_CONCURRENT_DOCUMENTS,
+            &mut errors,
+        );
+        let max_compared_documents = positive(
+            &var,
+            "MAX_COMPARED_DOCUMENTS",
+            DEFAULT_MAX_COMPARED_DOCUMENTS,
+            &mut errors,
+        );
+
+        let sink = match var("SUMMARY_SINK").as_deref() {
+            None | Some("s3") => Some(SinkConfig::S3 {
+                bucket: var("SUMMARY_BUCKET").filter(|bucket| !bucket.trim().is_empty()),
+            }),
+            Some("dynamodb") => required(&var, "SUMMARY_TABLE", &mut errors)
+                .map(|table_name| SinkConfig::DynamoDb { table_name }),
+            Some(value) => {
+                errors.push(format!(
+                    r#"Invalid "SUMMARY_SINK" environment variable {value:?}, expected "s3" or "dynamodb"."#
+                ));
+                None
+            }
+        };
+
+        let source = var("TASKS");
+        let tasks = match TaskCatalog::from_yaml(source.as_deref().unwrap_or(DEFAULT_TASKS)) {
+            Ok(tasks) => Some(tasks),
+            Err(error) => {
+                errors.push(format!(r#""TASKS" environment variable: {error}."#));
+                None
+            }
+        };
+
+        let citation_format = var("CITATION_FORMAT").and_then(|value| {
+            let format = CitationFormat::parse(&value);
+            if format.is_none() {
+                errors.push(format!(
+                    r#"Invalid "CITATION_FORMAT" environment variable {value:?}, expected "footnotes" or "markdown"."#
+                ));
+            }
+
+            format
+        });
+
+        let generation = match var("GENERATION") {
+            None => Some(GenerationSettings::default()),
+            Some(source) => match GenerationSettings::from_yaml(&source) {
+                Ok(generation) => Some(generation),
+                Err(error) => {
+                    errors.push(format!(r#""GENERATION" environment variable: {error}."#));
+                    None
+                }
+            },
+        };
+
+        match (
+            model_arn,
+            max_concurrent_documents,
+            max_compared_documents,
+            sink,
+            tasks,
+            generation,
+        ) {
+            (
+                Some(model_arn),
+                Some(max_concurrent_documents),
+                Some(max_compared_documents),
+                Some(sink),
+                Some(tasks),
+                Some(generation),
+            ) if errors.is_empty() => Ok(Config {
+                model_arn,
+                max_concurrent_documents,
+                max_compared_documents,
+                sink,
+                tasks,
+                citation_format,
+                generation,
+            }),
+            _ => Err(ConfigError(errors)),
+        }
+    }
+}
+
+fn required(
+    var: &impl Fn(&str) -> Option<String>,
+    name: &str,
+    errors: &mut Vec<String>,
+) -> Option<String> {
+    let value = var(name).filter(|value| !value.trim().is_empty());
+    if value.is_none() {
+        errors.push(format!(r#"Missing "{name}" environment variable."#));
+    }
+
+    value
+}
+
+fn positive(
+    var: &impl Fn(&str) -> Option<String>,
+    name: &str,
+    default: usize,
+    errors: &mut Vec<String>,
+) -> Option<usize> {
+    match var(name) {
+        None => Some(default),
+        Some(value) => match value.parse::<usize>() {
+            Ok(number) if number > 0 => Some(number),
+            _ => {
+                errors.push(format!(
+                    r#"Invalid "{name}" environment variable {value:?}, expected a positive number."#
+                ));
+                None
+            }
+        },
+    }
+}
+
+/// Every problem found in the configuration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigError(pub Vec<String>);
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid configuration: {}", self.0.join(" "))
+    }
+}
+
+impl std::error::Error for ConfigError {}
@@ -0,0 +1,98 @@
+/**
+ * Documents to chat with, taken from the records of the S3 event notifications.
+ *
+ * Reference: https://docs.aws.amazon.com/AmazonS3/latest/userguide/notification-content-structure.html
+ */
+use aws_lambda_events::event::s3::S3EventRecord;
+use percent_encoding::percent_decode_str;
+use serde::Serialize;
+use std::fmt;
+
+/// Object uploaded to S3, with its key decoded.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct S3Document {
+    pub bucket: String,
+    pub key: String,
+}
+
+impl S3Document {
+    /// Parses an S3 URI, `s3://<bucket>/<key>`.
+    pub fn from_uri(uri: &str) -> Option<Self> {
+        let (bucket, key) = uri.strip_prefix("s3://")?.split_once('/')?;
+        if bucket.is_empty() || key.is_empty() {
+            return None;
+        }
+
+        Some(S3Document {
+            bucket: bucket.to_string(),
+            key: key.to_string(),
+        })
+    }
+
+    pub fn uri(&self) -> String {
+        format!("s3://{}/{}", self.bucket, self.key)
+    }
+}
+
+impl TryFrom<&S3EventRecord> for S3Document {
+    type Error = RecordError;
+
+    fn try_from(record: &S3EventRecord) -> Result<Self, Self::Error> {
+        let bucket = record
+            .s3
+            .bucket
+            .name
+            .clone()
+            .filter(|bucket| !bucket.is_empty())
+            .ok_or(RecordError::MissingBucket)?;
+        let key = record
+            .s3
+            .object
+            .key
+            .as_deref()
+            .filter(|key| !key.is_empty())
+            .ok_or_else(|| RecordError::MissingKey {
+                bucket: bucket.clone(),
+            })?;
+        let key = decode_key(key).ok_or_else(|| RecordError::InvalidKey {
+            bucket: bucket.clone(),
+            key: key.to_string(),
+        })?;
+
+        Ok(S3Document { bucket, key })
+    }
+}
+
+/// Keys are URL-encoded in event notifications, with spaces as `+`, e.g. `my+document%281%29.pdf`.
+/// Returns `None` when the decoded key isn't valid UTF-8.
+pub fn decode_key(key: &str) -> Option<String> {
+    percent_decode_str(&key.replace('+', " "))
+        .decode_utf8()
+        .ok()
+        .map(|key| key.into_owned())
+}
+
+/// Why a record doesn't point to a document.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordError {
+    MissingBucket,
+    MissingKey { bucket: String },
+    InvalidKey { bucket: String, key: String },
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordError::MissingBucket => write!(f, "Malformed record: no bucket name"),
+            RecordError::MissingKey { bucket } => {
+                write!(f, "Malformed record: no object key in bucket {bucket:?}")
+            }
+            RecordError::InvalidKey { bucket, key } => write!(
+                f,
+                "Malformed record: object key {key:?} in bucket {bucket:?} isn't URL-encoded UTF-8"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RecordError {}
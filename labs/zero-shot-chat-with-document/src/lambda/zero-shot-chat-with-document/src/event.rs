@@ -0,0 +1,132 @@
+/**
+ * Events the lambda function is invoked with: notifications of the documents uploaded to S3, and
+ * HTTP requests with questions about them, through its function URL or API Gateway.
+ *
+ * Reference:
+ * - https://docs.aws.amazon.com/lambda/latest/dg/urls-invocation.html#urls-payloads
+ * - https://docs.aws.amazon.com/apigateway/latest/developerguide/http-api-develop-integrations-lambda.html
+ */
+use crate::report::BatchReport;
+use aws_lambda_events::event::s3::S3Event;
+use base64::{prelude::BASE64_STANDARD, Engine};
+use serde::{de::DeserializeOwned, de::Error as _, Deserialize, Deserializer, Serialize};
+use std::{borrow::Cow, collections::HashMap};
+
+#[derive(Debug, Clone)]
+pub enum Request {
+    Uploads(S3Event),
+    Http(HttpRequest),
+}
+
+impl<'de> Deserialize<'de> for Request {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let event = serde_json::Value::deserialize(deserializer)?;
+
+        // S3 notifications are the only events with "Records", and HTTP requests with "rawPath"
+        if event.get("Records").is_some() {
+            serde_json::from_value(event)
+                .map(Request::Uploads)
+                .map_err(D::Error::custom)
+        } else if event.get("rawPath").is_some() {
+            serde_json::from_value(event)
+                .map(Request::Http)
+                .map_err(D::Error::custom)
+        } else {
+            Err(D::Error::custom(
+                "unsupported event, expected an S3 notification or an HTTP request",
+            ))
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Response {
+    Report(BatchReport),
+    Http(HttpResponse),
+}
+
+/// The part of the HTTP request payload used, the same in function URLs and API Gateway.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpRequest {
+    /// Path of the request, starting with the stage with API Gateway, e.g. `/prod/compare`.
+    pub raw_path: String,
+    #[serde(default)]
+    pub request_context: HttpRequestContext,
+    #[serde(default)]
+    pub body: Option<String>,
+    #[serde(default)]
+    pub is_base64_encoded: bool,
+}
+
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct HttpRequestContext {
+    /// Stage of API Gateway, `$default` for function URLs and the default stage.
+    #[serde(default)]
+    pub stage: Option<String>,
+}
+
+/// What an HTTP request asks for, chosen by its path.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Route {
+    /// `/`: a question about a document, see [`crate::chat::Question`].
+    Answer,
+    /// `/compare`: a question comparing documents, see [`crate::compare::Comparison`].
+    Compare,
+}
+
+impl HttpRequest {
+    /// Route of the request, ignoring the stage of API Gateway and any trailing slash, `None`
+    /// when there is none for its path.
+    pub fn route(&self) -> Option<Route> {
+        let path = self
+            .request_context
+            .stage
+            .as_deref()
+            .filter(|stage| *stage != "$default")
+            .and_then(|stage| self.raw_path.strip_prefix('/')?.strip_prefix(stage))
+            .filter(|path| path.is_empty() || path.starts_with('/'))
+            .unwrap_or(&self.raw_path);
+
+        match path.trim_end_matches('/') {
+            "" => Some(Route::Answer),
+            "/compare" => Some(Route::Compare),
+            _ => None,
+        }
+    }
+
+    /// Parses the JSON body of the request.
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, String> {
+        let body = self.body.as_deref().unwrap_or_default();
+        let body = if self.is_base64_encoded {
+            Cow::Owned(
+                BASE64_STANDARD
+                    .decode(body)
+                    .map_err(|error| format!("Invalid base64 request body: {error}"))?,
+            )
+        } else {
+            Cow::Borrowed(body.as_bytes())
+        };
+
+        serde_json::from_slice(&body).map_err(|error| format!("Invalid request body: {error}"))
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpResponse {
+    pub status_code: u16,
+    pub headers: HashMap<String, String>,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn json<T: Serialize>(status_code: u16, body: &T) -> Self {
+        HttpResponse {
+            status_code,
+            headers: HashMap::from([("content-type".to_string(), "application/json".to_string())]),
+            body: serde_json::to_string(body).unwrap_or_default(),
+        }
+    }
+}
@@ -0,0 +1,286 @@
+/**
+ * Settings of the generation of the responses: the prompt template around the document, the
+ * inference parameters, the guardrail applied, and fields specific to the model.
+ *
+ * Reference:
+ * - https://docs.aws.amazon.com/bedrock/latest/APIReference/API_agent-runtime_ExternalSourcesGenerationConfiguration.html
+ * - https://docs.aws.amazon.com/bedrock/latest/userguide/kb-test-config.html
+ */
+use aws_sdk_bedrockagentruntime::{
+    error::BuildError,
+    types::{
+        ExternalSourcesGenerationConfiguration, GuardrailConfiguration, InferenceConfig,
+        PromptTemplate, TextInferenceConfig,
+    },
+};
+use aws_smithy_types::Document;
+use serde::Deserialize;
+use std::{collections::HashMap, fmt};
+
+/// Placeholder of the prompt template replaced with the passages of the document.
+pub const SEARCH_RESULTS_PLACEHOLDER: &str = "$search_results$";
+
+/// Bedrock accepts up to 4 stop sequences.
+pub const MAX_STOP_SEQUENCES: usize = 4;
+
+/// Every setting is optional, the model defaults are used for the ones not set.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerationSettings {
+    /// Prompt sent to the model, which must include [`SEARCH_RESULTS_PLACEHOLDER`].
+    #[serde(default)]
+    pub prompt_template: Option<String>,
+    #[serde(default)]
+    pub inference: Option<InferenceSettings>,
+    #[serde(default)]
+    pub guardrail: Option<GuardrailSettings>,
+    /// Fields of the model request not covered by the inference settings, e.g. `top_k`.
+    #[serde(default)]
+    pub additional_model_request_fields: Option<serde_json::Map<String, serde_json::Value>>,
+}
+
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct InferenceSettings {
+    /// Between 0 and 1, lower values give more predictable responses.
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    /// Between 0 and 1, share of the most likely tokens the model picks from.
+    #[serde(default)]
+    pub top_p: Option<f32>,
+    #[serde(default)]
+    pub max_tokens: Option<i32>,
+    #[serde(default)]
+    pub stop_sequences: Option<Vec<String>>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct GuardrailSettings {
+    pub id: String,
+    /// Number of a version of the guardrail, or `DRAFT`.
+    pub version: String,
+}
+
+impl GenerationSettings {
+    /// Parses the settings from YAML, or JSON since it is valid YAML too.
+    pub fn from_yaml(source: &str) -> Result<Self, GenerationError> {
+        let settings: GenerationSettings = serde_yaml::from_str(source)
+            .map_err(|error| GenerationError::Parse(error.to_string()))?;
+        settings.validate()?;
+
+        Ok(settings)
+    }
+
+    pub fn validate(&self) -> Result<(), GenerationError> {
+        if let Some(prompt_template) = &self.prompt_template {
+            if !prompt_template.contains(SEARCH_RESULTS_PLACEHOLDER) {
+                return Err(GenerationError::MissingSearchResults);
+            }
+        }
+
+        if let Some(inference) = &self.inference {
+            for (setting, value) in [
+                ("temperature", inference.temperature),
+                ("topP", inference.top_p),
+            ] {
+                if let Some(value) = value.filter(|value| !(0.0..=1.0).contains(value)) {
+                    return Err(GenerationError::OutOfRange { setting, value });
+                }
+            }
+            if let Some(max_tokens) = inference.max_tokens.filter(|max_tokens| *max_tokens <= 0) {
+                return Err(GenerationError::InvalidMaxTokens(max_tokens));
+            }
+            if let Some(stop_sequences) = &inference.stop_sequences {
+                if stop_sequences.len() > MAX_STOP_SEQUENCES {
+                    return Err(GenerationError::TooManyStopSequences(stop_sequences.len()));
+                }
+            }
+        }
+
+        if let Some(guardrail) = &self.guardrail {
+            if guardrail.id.trim().is_empty() {
+                return Err(GenerationError::MissingGuardrailId);
+            }
+            let version = guardrail.version.trim();
+            if version != "DRAFT"
+                && (version.is_empty() || !version.chars().all(|c| c.is_ascii_digit()))
+            {
+                return Err(GenerationError::InvalidGuardrailVersion(
+                    guardrail.version.clone(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// These settings with the ones set in `overrides` instead, e.g. the ones of a task.
+    pub fn merged(&self, overrides: Option<&GenerationSettings>) -> GenerationSettings {
+        let Some(overrides) = overrides else {
+            return self.clone();
+        };
+
+        let inference = match (&self.inference, &overrides.inference) {
+            (Some(inference), Some(overrides)) => Some(InferenceSettings {
+                temperature: overrides.temperature.or(inference.temperature),
+                top_p: overrides.top_p.or(inference.top_p),
+                max_tokens: overrides.max_tokens.or(inference.max_tokens),
+                stop_sequences: overrides
+                    .stop_sequences
+                    .clone()
+                    .or_else(|| inference.stop_sequences.clone()),
+            }),
+            (inference, overrides) => overrides.clone().or_else(|| inference.clone()),
+        };
+        let additional_model_request_fields = match (
+            &self.additional_model_request_fields,
+            &overrides.additional_model_request_fields,
+        ) {
+            (Some(fields), Some(overrides)) => {
+                let mut fields = fields.clone();
+                fields.extend(overrides.clone());
+                Some(fields)
+            }
+            (fields, overrides) => overrides.clone().or_else(|| fields.clone()),
+        };
+
+        GenerationSettings {
+            prompt_template: overrides
+                .prompt_template
+                .clone()
+                .or_else(|| self.prompt_template.clone()),
+            inference,
+            guardrail: overrides
+                .guardrail
+                .clone()
+                .or_else(|| self.guardrail.clone()),
+            additional_model_request_fields,
+        }
+    }
+
+    /// Generation configuration of the request, `None` when no setting is set so the model
+    /// defaults are used.
+    pub fn configuration(
+        &self,
+    ) -> Result<Option<ExternalSourcesGenerationConfiguration>, BuildError> {
+        if self == &GenerationSettings::default() {
+            return Ok(None);
+        }
+
+        let prompt_template = self.prompt_template.as_ref().map(|prompt_template| {
+            PromptTemplate::builder()
+                .text_prompt_template(prompt_template)
+                .build()
+        });
+        let inference_config = self.inference.as_ref().map(|inference| {
+            let text_inference_config = TextInferenceConfig::builder()
+                .set_temperature(inference.temperature)
+                .set_top_p(inference.top_p)
+                .set_max_tokens(inference.max_tokens)
+                .set_stop_sequences(inference.stop_sequences.clone())
+                .build();
+
+            InferenceConfig::builder()
+                .text_inference_config(text_inference_config)
+                .build()
+        });
+        let guardrail_configuration = self
+            .guardrail
+            .as_ref()
+            .map(|guardrail| {
+                GuardrailConfiguration::builder()
+                    .guardrail_id(guardrail.id.trim())
+                    .guardrail_version(guardrail.version.trim())
+                    .build()
+            })
+            .transpose()?;
+        let additional_model_request_fields =
+            self.additional_model_request_fields.as_ref().map(|fields| {
+                fields
+                    .iter()
+                    .map(|(name, value)| (name.clone(), document(value)))
+                    .collect::<HashMap<_, _>>()
+            });
+
+        Ok(Some(
+            ExternalSourcesGenerationConfiguration::builder()
+                .set_prompt_template(prompt_template)
+                .set_inference_config(inference_config)
+                .set_guardrail_configuration(guardrail_configuration)
+                .set_additional_model_request_fields(additional_model_request_fields)
+                .build(),
+        ))
+    }
+}
+
+/// The JSON value as a document of the SDK.
+fn document(value: &serde_json::Value) -> Document {
+    match value {
+        serde_json::Value::Null => Document::Null,
+        serde_json::Value::Bool(value) => Document::from(*value),
+        serde_json::Value::Number(number) => number
+            .as_u64()
+            .map(Document::from)
+            .or_else(|| number.as_i64().map(Document::from))
+            .unwrap_or_else(|| Document::from(number.as_f64().unwrap_or_default())),
+        serde_json::Value::String(value) => Document::from(value.clone()),
+        serde_json::Value::Array(values) => {
+            Document::from(values.iter().map(document).collect::<Vec<_>>())
+        }
+        serde_json::Value::Object(fields) => Document::from(
+            fields
+                .iter()
+                .map(|(name, value)| (name.clone(), document(value)))
+                .collect::<HashMap<_, _>>(),
+        ),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GenerationError {
+    Parse(String),
+    /// The prompt template doesn't include [`SEARCH_RESULTS_PLACEHOLDER`].
+    MissingSearchResults,
+    /// A setting that must be between 0 and 1 isn't.
+    OutOfRange {
+        setting: &'static str,
+        value: f32,
+    },
+    InvalidMaxTokens(i32),
+    TooManyStopSequences(usize),
+    MissingGuardrailId,
+    InvalidGuardrailVersion(String),
+}
+
+impl fmt::Display for GenerationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GenerationError::Parse(error) => write!(f, "Invalid generation settings: {error}"),
+            GenerationError::MissingSearchResults => write!(
+                f,
+                "Invalid generation settings: promptTemplate must include {SEARCH_RESULTS_PLACEHOLDER}"
+            ),
+            GenerationError::OutOfRange { setting, value } => write!(
+                f,
+                "Invalid generation settings: {setting} is {value}, expected a number between 0 and 1"
+            ),
+            GenerationError::InvalidMaxTokens(max_tokens) => write!(
+                f,
+                "Invalid generation settings: maxTokens is {max_tokens}, expected a positive number"
+            ),
+            GenerationError::TooManyStopSequences(count) => write!(
+                f,
+                "Invalid generation settings: {count} stopSequences, expected at most {MAX_STOP_SEQUENCES}"
+            ),
+            GenerationError::MissingGuardrailId => {
+                write!(f, "Invalid generation settings: missing guardrail id")
+            }
+            GenerationError::InvalidGuardrailVersion(version) => write!(
+                f,
+                "Invalid generation settings: guardrail version {version:?}, expected a version number or \"DRAFT\""
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GenerationError {}
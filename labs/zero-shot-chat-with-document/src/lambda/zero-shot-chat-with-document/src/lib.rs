@@ -0,0 +1,12 @@
+pub mod chat;
+pub mod citation;
+pub mod compare;
+pub mod config;
+pub mod document;
+pub mod event;
+pub mod generation;
+pub mod report;
+pub mod sink;
+pub mod source;
+pub mod summary;
+pub mod task;
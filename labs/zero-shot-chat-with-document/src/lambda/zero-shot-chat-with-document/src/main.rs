@@ -1,74 +1,411 @@
-use aws_lambda_events::event::s3::S3Event;
-use aws_sdk_bedrockagentruntime::types::{
-    ExternalSource, ExternalSourceType, ExternalSourcesRetrieveAndGenerateConfiguration,
-    RetrieveAndGenerateConfiguration, RetrieveAndGenerateInput, RetrieveAndGenerateType,
-    S3ObjectDoc,
-};
+use aws_lambda_events::event::s3::{S3Event, S3EventRecord};
+use aws_sdk_bedrockagentruntime::{error::DisplayErrorContext, types::RetrieveAndGenerateInput};
+use chrono::Utc;
+use futures::{stream, StreamExt};
 use lambda_runtime::{
     run, service_fn,
     tracing::{self, instrument},
     Error, LambdaEvent,
 };
+use zero_shot_chat_with_document::{
+    chat::{Answer, ErrorBody, Question},
+    citation::{self, Citation},
+    compare::{compare, CompareError, Comparison},
+    config::{Config, SinkConfig},
+    document::S3Document,
+    event::{HttpRequest, HttpResponse, Request, Response, Route},
+    report::{BatchReport, Outcome, RecordReport},
+    sink::{DynamoDbSummarySink, S3SummarySink, SummarySink},
+    source::{retrieve_and_generate_configuration, DocumentSource},
+    summary::{Summary, SUMMARY_SUFFIX},
+    task::{ObjectAttributes, TaskPrompt},
+};
 
-/// Prompt used to query the foundational model
-const PROMPT: &str = "Summarize in few sentences the given document.";
-
-#[instrument(name = "zero_shot_chat_with_document_handler", skip(bedrock_agent_runtime_client), fields(req_id = %event.context.request_id))]
-async fn handler(
-    event: LambdaEvent<S3Event>,
+#[instrument(name = "zero_shot_chat_with_document_handler", skip(config, bedrock_agent_runtime_client, s3_client, sink), fields(req_id = %event.context.request_id))]
+async fn handler<S: SummarySink + Sync>(
+    event: LambdaEvent<Request>,
+    config: &Config,
     bedrock_agent_runtime_client: &aws_sdk_bedrockagentruntime::Client,
-) -> Result<String, Error> {
+    s3_client: &aws_sdk_s3::Client,
+    sink: &S,
+) -> Result<Response, Error> {
     tracing::info!("handler invoked");
 
-    let model_arn =
-        std::env::var("MODEL_ARN").expect("Expected MODEL_ARN environment variable to be defined");
-    let records = event.payload.records;
-    let Some(first_record) = records.first() else {
-        return Err("No record to process in event received".into());
+    let response = match event.payload {
+        Request::Uploads(s3_event) => Response::Report(
+            process_uploads(
+                s3_event,
+                config,
+                bedrock_agent_runtime_client,
+                s3_client,
+                sink,
+            )
+            .await,
+        ),
+        Request::Http(request) => Response::Http(match request.route() {
+            Some(Route::Answer) => answer(&request, config, bedrock_agent_runtime_client).await,
+            Some(Route::Compare) => {
+                answer_comparison(&request, config, bedrock_agent_runtime_client, s3_client).await
+            }
+            None => not_found(&request.raw_path),
+        }),
     };
-    let Some(bucket_name) = first_record.s3.bucket.name.as_ref() else {
-        return Err("No bucket name found in record to process".into());
+
+    Ok(response)
+}
+
+/// Runs the task of every uploaded document.
+async fn process_uploads<S: SummarySink + Sync>(
+    s3_event: S3Event,
+    config: &Config,
+    bedrock_agent_runtime_client: &aws_sdk_bedrockagentruntime::Client,
+    s3_client: &aws_sdk_s3::Client,
+    sink: &S,
+) -> BatchReport {
+    let records = s3_event.records;
+    if records.is_empty() {
+        tracing::warn!("No record to process in event received.");
+    }
+
+    // Records are processed concurrently, up to the configured limit, and reported in order
+    let reports = stream::iter(records.iter().enumerate())
+        .map(|(index, record)| {
+            process(
+                index,
+                record,
+                config,
+                bedrock_agent_runtime_client,
+                s3_client,
+                sink,
+            )
+        })
+        .buffered(config.max_concurrent_documents)
+        .collect::<Vec<_>>()
+        .await;
+    let report = BatchReport::new(reports);
+
+    tracing::info!(
+        { succeeded = report.succeeded, failed = report.failed, skipped = report.skipped },
+        "Records processed."
+    );
+
+    report
+}
+
+/// Answers a question about a document, in S3 or sent along, continuing the conversation of its
+/// session when given.
+async fn answer(
+    request: &HttpRequest,
+    config: &Config,
+    bedrock_agent_runtime_client: &aws_sdk_bedrockagentruntime::Client,
+) -> HttpResponse {
+    let question: Question = match request.json() {
+        Ok(question) => question,
+        Err(error) => return bad_request(error),
     };
-    let Some(object_key) = first_record.s3.object.key.as_ref() else {
-        return Err("".into());
+    // Inline documents are checked here, Bedrock would only reject them once received
+    let source = match question.source() {
+        Ok(source) => source,
+        Err(error) => return bad_request(error),
     };
+    if question.question.trim().is_empty() {
+        return bad_request("Missing question.".to_string());
+    }
 
-    tracing::info!({ %bucket_name, object_key }, "First record retrieved.");
+    tracing::info!({ document = %source, session_id = ?question.session_id }, "Question received.");
 
-    let input = RetrieveAndGenerateInput::builder().text(PROMPT).build()?;
-    let retrieve_and_generate_type = RetrieveAndGenerateType::ExternalSources;
-    let s3_document_uri = format!("s3://{bucket_name}/{object_key}");
-    let s3_retrieval_doc = S3ObjectDoc::builder()
-        .set_uri(Some(s3_document_uri))
-        .build()?;
-    let retrieval_s3_sources = ExternalSource::builder()
-        .set_source_type(Some(ExternalSourceType::S3))
-        .set_s3_location(Some(s3_retrieval_doc))
-        .build()?;
-    let external_source_retrieval_configuration =
-        ExternalSourcesRetrieveAndGenerateConfiguration::builder()
-            .set_model_arn(Some(model_arn))
-            .set_sources(Some(vec![retrieval_s3_sources]))
-            .build()?;
-    let retrieve_and_generate_configuration = RetrieveAndGenerateConfiguration::builder()
-        .set_type(Some(retrieve_and_generate_type))
-        .set_external_sources_configuration(Some(external_source_retrieval_configuration))
+    let input = match RetrieveAndGenerateInput::builder()
+        .text(&question.question)
+        .build()
+    {
+        Ok(input) => input,
+        Err(error) => return internal_error(error.to_string()),
+    };
+    let configuration =
+        match retrieve_and_generate_configuration(&source, &config.generation, &config.model_arn) {
+            Ok(configuration) => configuration,
+            Err(error) => return internal_error(error.to_string()),
+        };
+
+    let response = bedrock_agent_runtime_client
+        .retrieve_and_generate()
+        .input(input)
+        .set_session_id(question.session_id.clone())
+        .retrieve_and_generate_configuration(configuration)
+        .send()
+        .await;
+
+    match response {
+        Ok(response) => {
+            let text = response
+                .output()
+                .map(|output| output.text().to_string())
+                .unwrap_or_default();
+            let citations: Vec<Citation> =
+                response.citations().iter().map(Citation::from).collect();
+            let answer = Answer {
+                rendered: question
+                    .citation_format
+                    .map(|format| citation::render(&text, &citations, format)),
+                answer: text,
+                session_id: response.session_id().to_string(),
+                citations,
+                guardrail_action: response
+                    .guardrail_action()
+                    .map(|action| action.as_str().to_string()),
+            };
+            tracing::info!("Answer: {:#?}", answer.answer);
+
+            HttpResponse::json(200, &answer)
+        }
+        // Unknown or expired sessions, and documents the model can't read
+        Err(error)
+            if error
+                .as_service_error()
+                .is_some_and(|error| error.is_validation_exception()) =>
+        {
+            bad_request(DisplayErrorContext(&error).to_string())
+        }
+        Err(error) => internal_error(format!(
+            "Couldn't answer about {source}: {}",
+            DisplayErrorContext(&error)
+        )),
+    }
+}
+
+/// Answers a question comparing several documents in S3.
+async fn answer_comparison(
+    request: &HttpRequest,
+    config: &Config,
+    bedrock_agent_runtime_client: &aws_sdk_bedrockagentruntime::Client,
+    s3_client: &aws_sdk_s3::Client,
+) -> HttpResponse {
+    let comparison: Comparison = match request.json() {
+        Ok(comparison) => comparison,
+        Err(error) => return bad_request(error),
+    };
+
+    match compare(&comparison, config, bedrock_agent_runtime_client, s3_client).await {
+        Ok(answer) => {
+            tracing::info!("Answer: {:#?}", answer.answer);
+
+            HttpResponse::json(200, &answer)
+        }
+        Err(CompareError::Invalid(error)) => bad_request(error),
+        Err(CompareError::Failed(error)) => internal_error(error),
+    }
+}
+
+fn bad_request(error: String) -> HttpResponse {
+    tracing::warn!("Invalid request: {error}");
+
+    HttpResponse::json(400, &ErrorBody { error })
+}
+
+fn not_found(path: &str) -> HttpResponse {
+    tracing::warn!("No route for path {path:?}");
+
+    HttpResponse::json(
+        404,
+        &ErrorBody {
+            error: format!("Not found: {path}, expected / or /compare."),
+        },
+    )
+}
+
+fn internal_error(error: String) -> HttpResponse {
+    tracing::error!("{error}");
+
+    HttpResponse::json(500, &ErrorBody { error })
+}
+
+/// Runs the task of the document of a record and stores its result, a failure only affects the
+/// report of that record.
+async fn process<S: SummarySink>(
+    index: usize,
+    record: &S3EventRecord,
+    config: &Config,
+    bedrock_agent_runtime_client: &aws_sdk_bedrockagentruntime::Client,
+    s3_client: &aws_sdk_s3::Client,
+    sink: &S,
+) -> RecordReport {
+    let document = match S3Document::try_from(record) {
+        Ok(document) => document,
+        Err(error) => {
+            tracing::error!(index, "{error}");
+
+            return RecordReport {
+                index,
+                document: None,
+                outcome: Outcome::Failed {
+                    error: error.to_string(),
+                },
+            };
+        }
+    };
+
+    tracing::info!({ index, bucket_name = %document.bucket, object_key = %document.key }, "Record retrieved.");
+
+    // Without a "SUMMARY_BUCKET", summaries written next to their document trigger the function too
+    if document.key.ends_with(SUMMARY_SUFFIX) {
+        return RecordReport {
+            index,
+            document: Some(document),
+            outcome: Outcome::Skipped {
+                reason: "Document is a summary".to_string(),
+            },
+        };
+    }
+
+    let stored = async {
+        let attributes = object_attributes(&document, s3_client).await?;
+        let task_prompt = config.tasks.prompt(&document, &attributes)?;
+        tracing::info!(index, task = %task_prompt.task, "Task selected.");
+
+        let summary = run_task(
+            &document,
+            &task_prompt,
+            config,
+            bedrock_agent_runtime_client,
+        )
+        .await?;
+        let location = sink.store(&summary).await.map_err(|error| {
+            format!("Couldn't store the summary of {}: {error}", document.uri())
+        })?;
+
+        Ok::<_, Error>((summary, location))
+    };
+    let outcome = match stored.await {
+        Ok((summary, location)) => {
+            tracing::info!(index, "Summary stored at {:?}", location);
+
+            Outcome::Succeeded {
+                task: summary.task,
+                summary: summary.summary,
+                stored: location,
+            }
+        }
+        Err(error) => {
+            tracing::error!(index, "{error}");
+
+            Outcome::Failed {
+                error: error.to_string(),
+            }
+        }
+    };
+
+    RecordReport {
+        index,
+        document: Some(document),
+        outcome,
+    }
+}
+
+/// Metadata and tags of the uploaded document, which select its task and fill its prompt.
+async fn object_attributes(
+    document: &S3Document,
+    s3_client: &aws_sdk_s3::Client,
+) -> Result<ObjectAttributes, Error> {
+    let head = s3_client
+        .head_object()
+        .bucket(&document.bucket)
+        .key(&document.key)
+        .send()
+        .await
+        .map_err(|error| {
+            format!(
+                "Couldn't read the metadata of {}: {}",
+                document.uri(),
+                DisplayErrorContext(&error)
+            )
+        })?;
+    let tagging = s3_client
+        .get_object_tagging()
+        .bucket(&document.bucket)
+        .key(&document.key)
+        .send()
+        .await
+        .map_err(|error| {
+            format!(
+                "Couldn't read the tags of {}: {}",
+                document.uri(),
+                DisplayErrorContext(&error)
+            )
+        })?;
+
+    Ok(ObjectAttributes {
+        metadata: head.metadata().cloned().unwrap_or_default(),
+        tags: tagging
+            .tag_set()
+            .iter()
+            .map(|tag| (tag.key().to_string(), tag.value().to_string()))
+            .collect(),
+    })
+}
+
+async fn run_task(
+    document: &S3Document,
+    task_prompt: &TaskPrompt,
+    config: &Config,
+    bedrock_agent_runtime_client: &aws_sdk_bedrockagentruntime::Client,
+) -> Result<Summary, Error> {
+    let input = RetrieveAndGenerateInput::builder()
+        .text(&task_prompt.prompt)
         .build()?;
+    // The settings of the task take precedence over the configured ones
+    let generation = config.generation.merged(task_prompt.generation.as_ref());
+    let retrieve_and_generate_configuration = retrieve_and_generate_configuration(
+        &DocumentSource::S3(document.clone()),
+        &generation,
+        &config.model_arn,
+    )?;
 
+    let requested_at = Utc::now();
     let response = bedrock_agent_runtime_client
         .retrieve_and_generate()
         .input(input)
         .set_retrieve_and_generate_configuration(Some(retrieve_and_generate_configuration))
         .send()
-        .await?;
+        .await
+        .map_err(|error| {
+            format!(
+                "Couldn't run task \"{}\" on {}: {}",
+                task_prompt.task,
+                document.uri(),
+                DisplayErrorContext(&error)
+            )
+        })?;
     let response_output = response
         .output()
-        .ok_or("Expected text output defined from RetrieveAndGenerate response. Got None instead.")?
+        .ok_or_else(|| {
+            format!(
+                "Expected text output defined from RetrieveAndGenerate response for {}. Got None instead.",
+                document.uri()
+            )
+        })?
         .text();
 
     tracing::info!("Response: {:#?}", response_output);
 
-    Ok(response_output.into())
+    let citations: Vec<Citation> = response.citations().iter().map(Citation::from).collect();
+
+    Ok(Summary {
+        document: document.clone(),
+        task: task_prompt.task.clone(),
+        summary: response_output.to_string(),
+        rendered: config
+            .citation_format
+            .map(|format| citation::render(response_output, &citations, format)),
+        citations,
+        guardrail_action: response
+            .guardrail_action()
+            .map(|action| action.as_str().to_string()),
+        session_id: response.session_id().to_string(),
+        model_arn: config.model_arn.clone(),
+        prompt: task_prompt.prompt.clone(),
+        requested_at,
+        generated_at: Utc::now(),
+    })
 }
 
 #[tokio::main]
@@ -87,9 +424,42 @@ async fn main() -> Result<(), Error> {
         .with_target(false)
         .init();
 
+    let config = Config::from_env().inspect_err(|error| tracing::error!("{error}"))?;
     let sdk_config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
     let bedrock_agentruntime_client = aws_sdk_bedrockagentruntime::Client::new(&sdk_config);
-    let func = service_fn(|event| handler(event, &bedrock_agentruntime_client));
+    let s3_client = aws_sdk_s3::Client::new(&sdk_config);
+
+    match &config.sink {
+        SinkConfig::S3 { bucket } => {
+            let sink = S3SummarySink::new(s3_client.clone(), bucket.clone());
+            let func = service_fn(|event| {
+                handler(
+                    event,
+                    &config,
+                    &bedrock_agentruntime_client,
+                    &s3_client,
+                    &sink,
+                )
+            });
+
+            run(func).await
+        }
+        SinkConfig::DynamoDb { table_name } => {
+            let sink = DynamoDbSummarySink::new(
+                aws_sdk_dynamodb::Client::new(&sdk_config),
+                table_name.clone(),
+            );
+            let func = service_fn(|event| {
+                handler(
+                    event,
+                    &config,
+                    &bedrock_agentruntime_client,
+                    &s3_client,
+                    &sink,
+                )
+            });
 
-    run(func).await
+            run(func).await
+        }
+    }
 }
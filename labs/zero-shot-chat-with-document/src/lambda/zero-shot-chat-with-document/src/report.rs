@@ -0,0 +1,58 @@
+use crate::{document::S3Document, sink::SummaryLocation};
+use serde::Serialize;
+
+/// Result of every record of an S3 event, in the order they were received.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchReport {
+    pub succeeded: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub records: Vec<RecordReport>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordReport {
+    /// Position of the record in the event.
+    pub index: usize,
+    /// Unknown when the record is malformed.
+    #[serde(flatten)]
+    pub document: Option<S3Document>,
+    #[serde(flatten)]
+    pub outcome: Outcome,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "status", rename_all = "UPPERCASE")]
+pub enum Outcome {
+    Succeeded {
+        task: String,
+        summary: String,
+        stored: SummaryLocation,
+    },
+    Failed {
+        error: String,
+    },
+    Skipped {
+        reason: String,
+    },
+}
+
+impl BatchReport {
+    pub fn new(records: Vec<RecordReport>) -> Self {
+        let count = |matches: fn(&Outcome) -> bool| {
+            records
+                .iter()
+                .filter(|record| matches(&record.outcome))
+                .count()
+        };
+
+        BatchReport {
+            succeeded: count(|outcome| matches!(outcome, Outcome::Succeeded { .. })),
+            failed: count(|outcome| matches!(outcome, Outcome::Failed { .. })),
+            skipped: count(|outcome| matches!(outcome, Outcome::Skipped { .. })),
+            records,
+        }
+    }
+}
@@ -0,0 +1,143 @@
+/**
+ * Summaries stored in a DynamoDB table with `document` as partition key.
+ *
+ * Each summary is one item:
+ * - document: S (S3 URI of the document)
+ * - bucket: S
+ * - key: S
+ * - task: S
+ * - summary: S
+ * - citations: L of M { text: S, start: N, end: N, references: L of M { text: S, location: S } }
+ * - rendered: S, when a citation format is configured
+ * - guardrail_action: S, when the model reported one
+ * - session_id: S
+ * - model_arn: S
+ * - prompt: S
+ * - requested_at: S (RFC 3339)
+ * - generated_at: S (RFC 3339)
+ */
+use super::{SinkError, SummaryLocation, SummarySink};
+use crate::{citation::Citation, summary::Summary};
+use aws_sdk_dynamodb::{error::DisplayErrorContext, types::AttributeValue};
+use std::collections::HashMap;
+
+pub struct DynamoDbSummarySink {
+    client: aws_sdk_dynamodb::Client,
+    table_name: String,
+}
+
+impl DynamoDbSummarySink {
+    pub fn new(client: aws_sdk_dynamodb::Client, table_name: impl Into<String>) -> Self {
+        DynamoDbSummarySink {
+            client,
+            table_name: table_name.into(),
+        }
+    }
+}
+
+impl SummarySink for DynamoDbSummarySink {
+    async fn store(&self, summary: &Summary) -> Result<SummaryLocation, SinkError> {
+        self.client
+            .put_item()
+            .table_name(&self.table_name)
+            .set_item(Some(to_item(summary)))
+            .send()
+            .await
+            .map_err(|error| SinkError::Backend(DisplayErrorContext(error).to_string().into()))?;
+
+        Ok(SummaryLocation::DynamoDb {
+            table_name: self.table_name.clone(),
+            document: summary.document.uri(),
+        })
+    }
+}
+
+/// Item of the summary, with the attributes above.
+pub fn to_item(summary: &Summary) -> HashMap<String, AttributeValue> {
+    let mut item = HashMap::from([
+        (
+            "document".to_string(),
+            AttributeValue::S(summary.document.uri()),
+        ),
+        (
+            "bucket".to_string(),
+            AttributeValue::S(summary.document.bucket.clone()),
+        ),
+        (
+            "key".to_string(),
+            AttributeValue::S(summary.document.key.clone()),
+        ),
+        ("task".to_string(), AttributeValue::S(summary.task.clone())),
+        (
+            "summary".to_string(),
+            AttributeValue::S(summary.summary.clone()),
+        ),
+        (
+            "citations".to_string(),
+            AttributeValue::L(summary.citations.iter().map(citation).collect()),
+        ),
+        (
+            "session_id".to_string(),
+            AttributeValue::S(summary.session_id.clone()),
+        ),
+        (
+            "model_arn".to_string(),
+            AttributeValue::S(summary.model_arn.clone()),
+        ),
+        (
+            "prompt".to_string(),
+            AttributeValue::S(summary.prompt.clone()),
+        ),
+        (
+            "requested_at".to_string(),
+            AttributeValue::S(summary.requested_at.to_rfc3339()),
+        ),
+        (
+            "generated_at".to_string(),
+            AttributeValue::S(summary.generated_at.to_rfc3339()),
+        ),
+    ]);
+    if let Some(rendered) = &summary.rendered {
+        item.insert("rendered".to_string(), AttributeValue::S(rendered.clone()));
+    }
+    if let Some(guardrail_action) = &summary.guardrail_action {
+        item.insert(
+            "guardrail_action".to_string(),
+            AttributeValue::S(guardrail_action.clone()),
+        );
+    }
+
+    item
+}
+
+fn citation(citation: &Citation) -> AttributeValue {
+    let references = citation
+        .references
+        .iter()
+        .map(|reference| {
+            let mut attributes = HashMap::from([(
+                "text".to_string(),
+                AttributeValue::S(reference.text.clone()),
+            )]);
+            if let Some(location) = &reference.location {
+                attributes.insert("location".to_string(), AttributeValue::S(location.clone()));
+            }
+
+            AttributeValue::M(attributes)
+        })
+        .collect();
+
+    let mut attributes = HashMap::from([("references".to_string(), AttributeValue::L(references))]);
+    if let Some(text) = &citation.text {
+        attributes.insert("text".to_string(), AttributeValue::S(text.clone()));
+    }
+    if let Some(span) = citation.span {
+        attributes.insert(
+            "start".to_string(),
+            AttributeValue::N(span.start.to_string()),
+        );
+        attributes.insert("end".to_string(), AttributeValue::N(span.end.to_string()));
+    }
+
+    AttributeValue::M(attributes)
+}
@@ -0,0 +1,59 @@
+pub mod dynamodb;
+pub mod s3;
+
+pub use dynamodb::DynamoDbSummarySink;
+pub use s3::S3SummarySink;
+
+use crate::summary::Summary;
+use serde::Serialize;
+use std::{fmt, future::Future};
+
+/// Storage for the summaries of the uploaded documents.
+pub trait SummarySink {
+    /// Stores the summary, replacing any previous one of the same document.
+    fn store(
+        &self,
+        summary: &Summary,
+    ) -> impl Future<Output = Result<SummaryLocation, SinkError>> + Send;
+}
+
+/// Where a summary was stored.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum SummaryLocation {
+    S3 {
+        uri: String,
+    },
+    DynamoDb {
+        table_name: String,
+        document: String,
+    },
+}
+
+#[derive(Debug)]
+pub enum SinkError {
+    Serialization(serde_json::Error),
+    Backend(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl fmt::Display for SinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SinkError::Serialization(error) => write!(f, "Summary couldn't be serialized: {error}"),
+            SinkError::Backend(error) => write!(f, "Summary storage failed: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for SinkError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SinkError::Serialization(error) => Some(error),
+            SinkError::Backend(error) => Some(error.as_ref()),
+        }
+    }
+}
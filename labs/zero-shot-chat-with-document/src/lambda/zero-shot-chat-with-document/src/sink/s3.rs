@@ -0,0 +1,41 @@
+/**
+ * Summaries written as JSON next to their document, `<key>.summary.json`, or with the same key in
+ * another bucket.
+ */
+use super::{SinkError, SummaryLocation, SummarySink};
+use crate::summary::{Summary, SUMMARY_SUFFIX};
+use aws_sdk_s3::{error::DisplayErrorContext, primitives::ByteStream};
+
+pub struct S3SummarySink {
+    client: aws_sdk_s3::Client,
+    /// Bucket of the document when it isn't set.
+    bucket: Option<String>,
+}
+
+impl S3SummarySink {
+    pub fn new(client: aws_sdk_s3::Client, bucket: Option<String>) -> Self {
+        S3SummarySink { client, bucket }
+    }
+}
+
+impl SummarySink for S3SummarySink {
+    async fn store(&self, summary: &Summary) -> Result<SummaryLocation, SinkError> {
+        let bucket = self.bucket.as_ref().unwrap_or(&summary.document.bucket);
+        let key = format!("{}{SUMMARY_SUFFIX}", summary.document.key);
+        let body = serde_json::to_vec_pretty(summary).map_err(SinkError::Serialization)?;
+
+        self.client
+            .put_object()
+            .bucket(bucket)
+            .key(&key)
+            .content_type("application/json")
+            .body(ByteStream::from(body))
+            .send()
+            .await
+            .map_err(|error| SinkError::Backend(DisplayErrorContext(error).to_string().into()))?;
+
+        Ok(SummaryLocation::S3 {
+            uri: format!("s3://{bucket}/{key}"),
+        })
+    }
+}
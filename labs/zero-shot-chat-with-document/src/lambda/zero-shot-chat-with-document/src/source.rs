@@ -0,0 +1,232 @@
+/**
+ * Sources the model reads the document from: an object in S3, or its bytes sent inline along with
+ * the request, for documents that aren't in S3, e.g. sent in the body of a question.
+ *
+ * Reference:
+ * - https://docs.aws.amazon.com/bedrock/latest/APIReference/API_agent-runtime_ExternalSource.html
+ * - https://docs.aws.amazon.com/bedrock/latest/APIReference/API_agent-runtime_ByteContentDoc.html
+ * - https://docs.aws.amazon.com/bedrock/latest/userguide/knowledge-base-ds.html#kb-ds-supported-doc-formats-limits
+ */
+use crate::{document::S3Document, generation::GenerationSettings};
+use aws_sdk_bedrockagentruntime::{
+    error::BuildError,
+    primitives::Blob,
+    types::{
+        ByteContentDoc, ExternalSource, ExternalSourceType,
+        ExternalSourcesRetrieveAndGenerateConfiguration, RetrieveAndGenerateConfiguration,
+        RetrieveAndGenerateType, S3ObjectDoc,
+    },
+};
+use std::fmt;
+
+/// Largest document Bedrock accepts as byte content, 10 MiB.
+pub const MAX_INLINE_DOCUMENT_SIZE: usize = 10 * 1024 * 1024;
+
+/// Formats the model can read, by file extension.
+const SUPPORTED_CONTENT_TYPES: [(&str, &str); 9] = [
+    ("pdf", "application/pdf"),
+    ("txt", "text/plain"),
+    ("md", "text/markdown"),
+    ("html", "text/html"),
+    ("csv", "text/csv"),
+    ("doc", "application/msword"),
+    (
+        "docx",
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    ),
+    ("xls", "application/vnd.ms-excel"),
+    (
+        "xlsx",
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    ),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DocumentSource {
+    S3(S3Document),
+    Inline(InlineDocument),
+}
+
+impl fmt::Display for DocumentSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DocumentSource::S3(document) => write!(f, "{}", document.uri()),
+            DocumentSource::Inline(document) => write!(f, "{:?}", document.identifier),
+        }
+    }
+}
+
+/// Document sent as bytes, checked to be one Bedrock can read.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InlineDocument {
+    /// Name of the document, e.g. its file name, which the model sees.
+    pub identifier: String,
+    /// MIME type, without parameters.
+    pub content_type: String,
+    pub data: Vec<u8>,
+}
+
+impl InlineDocument {
+    /// Checks the document, guessing its content type from the extension of its identifier when
+    /// none is given.
+    pub fn new(
+        identifier: &str,
+        content_type: Option<&str>,
+        data: Vec<u8>,
+    ) -> Result<Self, InlineDocumentError> {
+        let identifier = identifier.trim();
+        if identifier.is_empty() {
+            return Err(InlineDocumentError::MissingIdentifier);
+        }
+
+        let content_type = match content_type {
+            Some(content_type) => {
+                // Parameters, e.g. "; charset=utf-8", aren't part of the type
+                let essence = content_type
+                    .split(';')
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_lowercase();
+                if !SUPPORTED_CONTENT_TYPES
+                    .iter()
+                    .any(|(_, supported)| *supported == essence)
+                {
+                    return Err(InlineDocumentError::UnsupportedContentType(
+                        content_type.to_string(),
+                    ));
+                }
+
+                essence
+            }
+            None => content_type_of(identifier)
+                .ok_or_else(|| InlineDocumentError::UnknownContentType(identifier.to_string()))?
+                .to_string(),
+        };
+
+        if data.is_empty() {
+            return Err(InlineDocumentError::Empty);
+        }
+        if data.len() > MAX_INLINE_DOCUMENT_SIZE {
+            return Err(InlineDocumentError::TooLarge(data.len()));
+        }
+
+        Ok(InlineDocument {
+            identifier: identifier.to_string(),
+            content_type,
+            data,
+        })
+    }
+}
+
+/// Content type of a supported file, from the extension of its name.
+pub fn content_type_of(file_name: &str) -> Option<&'static str> {
+    let (_, extension) = file_name.rsplit_once('.')?;
+    let extension = extension.to_lowercase();
+
+    SUPPORTED_CONTENT_TYPES
+        .iter()
+        .find(|(supported, _)| *supported == extension)
+        .map(|(_, content_type)| *content_type)
+}
+
+/// Generates with the given document as source, both for the tasks run on uploads and the
+/// questions about them, tuned with the generation settings.
+pub fn retrieve_and_generate_configuration(
+    source: &DocumentSource,
+    generation: &GenerationSettings,
+    model_arn: &str,
+) -> Result<RetrieveAndGenerateConfiguration, BuildError> {
+    let retrieve_and_generate_type = RetrieveAndGenerateType::ExternalSources;
+    // Bedrock reads a single external source per request
+    let external_sources = vec![external_source(source)?];
+    let external_source_retrieval_configuration =
+        ExternalSourcesRetrieveAndGenerateConfiguration::builder()
+            .set_model_arn(Some(model_arn.to_string()))
+            .set_sources(Some(external_sources))
+            .set_generation_configuration(generation.configuration()?)
+            .build()?;
+
+    RetrieveAndGenerateConfiguration::builder()
+        .set_type(Some(retrieve_and_generate_type))
+        .set_external_sources_configuration(Some(external_source_retrieval_configuration))
+        .build()
+}
+
+fn external_source(source: &DocumentSource) -> Result<ExternalSource, BuildError> {
+    match source {
+        DocumentSource::S3(document) => {
+            let s3_retrieval_doc = S3ObjectDoc::builder()
+                .set_uri(Some(document.uri()))
+                .build()?;
+
+            ExternalSource::builder()
+                .set_source_type(Some(ExternalSourceType::S3))
+                .set_s3_location(Some(s3_retrieval_doc))
+                .build()
+        }
+        DocumentSource::Inline(document) => {
+            let byte_content_doc = ByteContentDoc::builder()
+                .identifier(&document.identifier)
+                .content_type(&document.content_type)
+                .data(Blob::new(document.data.clone()))
+                .build()?;
+
+            ExternalSource::builder()
+                .set_source_type(Some(ExternalSourceType::ByteContent))
+                .set_byte_content(Some(byte_content_doc))
+                .build()
+        }
+    }
+}
+
+/// Why an inline document can't be sent to the model.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InlineDocumentError {
+    MissingIdentifier,
+    Empty,
+    /// Size of the document, in bytes.
+    TooLarge(usize),
+    UnsupportedContentType(String),
+    /// No content type was given, and it can't be guessed from the identifier.
+    UnknownContentType(String),
+}
+
+impl fmt::Display for InlineDocumentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InlineDocumentError::MissingIdentifier => {
+                write!(f, "Invalid document: missing name")
+            }
+            InlineDocumentError::Empty => write!(f, "Invalid document: no content"),
+            InlineDocumentError::TooLarge(size) => write!(
+                f,
+                "Invalid document: {size} bytes, it can't be larger than {MAX_INLINE_DOCUMENT_SIZE} bytes"
+            ),
+            InlineDocumentError::UnsupportedContentType(content_type) => write!(
+                f,
+                "Invalid document: content type {content_type:?} isn't supported, expected one of {}",
+                supported_content_types()
+            ),
+            InlineDocumentError::UnknownContentType(identifier) => write!(
+                f,
+                "Invalid document: no content type given, and {identifier:?} doesn't have the extension of a supported format ({})",
+                SUPPORTED_CONTENT_TYPES
+                    .iter()
+                    .map(|(extension, _)| *extension)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for InlineDocumentError {}
+
+fn supported_content_types() -> String {
+    SUPPORTED_CONTENT_TYPES
+        .iter()
+        .map(|(_, content_type)| *content_type)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
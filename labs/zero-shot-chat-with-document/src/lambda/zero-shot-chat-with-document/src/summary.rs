@@ -0,0 +1,36 @@
+/**
+ * Summary of a document, with what is needed to know how it was generated and where it comes from.
+ *
+ * Reference: https://docs.aws.amazon.com/bedrock/latest/APIReference/API_agent-runtime_RetrieveAndGenerate.html
+ */
+use crate::{citation::Citation, document::S3Document};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Suffix of the key of the summaries written to S3, documents with it are never summarized.
+pub const SUMMARY_SUFFIX: &str = ".summary.json";
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Summary {
+    pub document: S3Document,
+    /// Task run on the document, see [`crate::task::TaskCatalog`].
+    pub task: String,
+    /// Response of the model, a summary unless another task was selected.
+    pub summary: String,
+    pub citations: Vec<Citation>,
+    /// The summary with its citations, when a "CITATION_FORMAT" is configured.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rendered: Option<String>,
+    /// `INTERVENED` when a guardrail changed the response of the model.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guardrail_action: Option<String>,
+    /// Session of the conversation with the model, to ask follow-up questions about the summary.
+    pub session_id: String,
+    pub model_arn: String,
+    pub prompt: String,
+    /// When the summary was requested to the model.
+    pub requested_at: DateTime<Utc>,
+    /// When the model responded.
+    pub generated_at: DateTime<Utc>,
+}
@@ -0,0 +1,250 @@
+/**
+ * Tasks run on the uploaded documents, each with its own prompt, selected for every document from
+ * its metadata, tags or key prefix.
+ *
+ * Reference:
+ * - https://docs.aws.amazon.com/AmazonS3/latest/userguide/UsingMetadata.html#UserMetadata
+ * - https://docs.aws.amazon.com/AmazonS3/latest/userguide/object-tagging.html
+ */
+use crate::{
+    document::S3Document,
+    generation::{GenerationError, GenerationSettings},
+};
+use serde::Deserialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+};
+
+/// Tasks used when no "TASKS" is configured, see [`crate::config::Config`].
+pub const DEFAULT_TASKS: &str = include_str!("../tasks.yaml");
+
+/// Name of the metadata and tag that select the task of a document.
+pub const TASK_ATTRIBUTE: &str = "task";
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskCatalog {
+    default_task: String,
+    tasks: BTreeMap<String, Task>,
+    /// Task of the documents whose key starts with each prefix.
+    #[serde(default)]
+    prefixes: BTreeMap<String, String>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Task {
+    /// Template of the prompt sent to the model, with `{{variable}}` placeholders.
+    pub prompt: String,
+    /// Default values of the variables used in the prompt.
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+    /// Generation settings of the task, used instead of the configured ones they set.
+    #[serde(default)]
+    pub generation: Option<GenerationSettings>,
+}
+
+/// Metadata and tags of an uploaded object, which select its task and fill its prompt.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ObjectAttributes {
+    pub metadata: HashMap<String, String>,
+    pub tags: HashMap<String, String>,
+}
+
+/// Task selected for a document, with its prompt rendered.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskPrompt {
+    pub task: String,
+    pub prompt: String,
+    pub generation: Option<GenerationSettings>,
+}
+
+impl TaskCatalog {
+    /// Parses the tasks from YAML, or JSON since it is valid YAML too.
+    pub fn from_yaml(source: &str) -> Result<Self, TaskCatalogError> {
+        let catalog: TaskCatalog = serde_yaml::from_str(source)
+            .map_err(|error| TaskCatalogError::Parse(error.to_string()))?;
+        catalog.validate()?;
+
+        Ok(catalog)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Task> {
+        self.tasks.get(name)
+    }
+
+    /// Name of the task of a document: the one in its metadata, in its tags, the one of the
+    /// longest prefix of its key, or the default one.
+    pub fn select<'a>(&'a self, key: &str, attributes: &'a ObjectAttributes) -> &'a str {
+        attributes
+            .metadata
+            .get(TASK_ATTRIBUTE)
+            .or_else(|| attributes.tags.get(TASK_ATTRIBUTE))
+            .map(|task| task.trim())
+            .or_else(|| {
+                self.prefixes
+                    .iter()
+                    .filter(|(prefix, _)| key.starts_with(prefix.as_str()))
+                    .max_by_key(|(prefix, _)| prefix.len())
+                    .map(|(_, task)| task.as_str())
+            })
+            .unwrap_or(&self.default_task)
+    }
+
+    /// Selects the task of the document and renders its prompt.
+    pub fn prompt(
+        &self,
+        document: &S3Document,
+        attributes: &ObjectAttributes,
+    ) -> Result<TaskPrompt, TaskError> {
+        let name = self.select(&document.key, attributes);
+        let task = self
+            .get(name)
+            .ok_or_else(|| TaskError::Unknown(name.to_string()))?;
+
+        let file_name = document.key.rsplit('/').next().unwrap_or_default();
+        let mut variables = HashMap::from([
+            ("key", document.key.as_str()),
+            ("bucket", document.bucket.as_str()),
+            ("fileName", file_name),
+        ]);
+        for (variable, value) in task
+            .variables
+            .iter()
+            .chain(&attributes.tags)
+            .chain(&attributes.metadata)
+        {
+            variables.insert(variable.as_str(), value.as_str());
+        }
+
+        let prompt = render(&task.prompt, |variable| variables.get(variable).copied()).map_err(
+            |variable| TaskError::MissingVariable {
+                task: name.to_string(),
+                variable,
+            },
+        )?;
+
+        Ok(TaskPrompt {
+            task: name.to_string(),
+            prompt,
+            generation: task.generation.clone(),
+        })
+    }
+
+    fn validate(&self) -> Result<(), TaskCatalogError> {
+        if !self.tasks.contains_key(&self.default_task) {
+            return Err(TaskCatalogError::UnknownTask(self.default_task.clone()));
+        }
+        if let Some(task) = self
+            .prefixes
+            .values()
+            .find(|task| !self.tasks.contains_key(task.as_str()))
+        {
+            return Err(TaskCatalogError::UnknownTask(task.clone()));
+        }
+
+        for (name, task) in &self.tasks {
+            if task.prompt.trim().is_empty() {
+                return Err(TaskCatalogError::EmptyPrompt(name.clone()));
+            }
+            if let Err(variable) = render(&task.prompt, |_| Some("")) {
+                return Err(TaskCatalogError::UnclosedPlaceholder {
+                    task: name.clone(),
+                    variable,
+                });
+            }
+            if let Some(Err(error)) = task.generation.as_ref().map(GenerationSettings::validate) {
+                return Err(TaskCatalogError::Generation {
+                    task: name.clone(),
+                    error,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Replaces every `{{variable}}` of the template with its value, failing with the name of the
+/// first variable without one.
+pub fn render<'a>(
+    template: &str,
+    value: impl Fn(&str) -> Option<&'a str>,
+) -> Result<String, String> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let placeholder = &rest[start + 2..];
+        let Some(end) = placeholder.find("}}") else {
+            return Err(placeholder.to_string());
+        };
+        let variable = placeholder[..end].trim();
+        rendered.push_str(value(variable).ok_or_else(|| variable.to_string())?);
+        rest = &placeholder[end + 2..];
+    }
+    rendered.push_str(rest);
+
+    Ok(rendered)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskCatalogError {
+    Parse(String),
+    /// The default task or the task of a prefix isn't defined.
+    UnknownTask(String),
+    EmptyPrompt(String),
+    UnclosedPlaceholder {
+        task: String,
+        variable: String,
+    },
+    Generation {
+        task: String,
+        error: GenerationError,
+    },
+}
+
+impl fmt::Display for TaskCatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskCatalogError::Parse(error) => write!(f, "Invalid tasks: {error}"),
+            TaskCatalogError::UnknownTask(task) => {
+                write!(f, "Invalid tasks: task \"{task}\" is not defined")
+            }
+            TaskCatalogError::EmptyPrompt(task) => {
+                write!(f, "Invalid tasks: task \"{task}\" has an empty prompt")
+            }
+            TaskCatalogError::UnclosedPlaceholder { task, variable } => write!(
+                f,
+                "Invalid tasks: prompt of task \"{task}\" has an unclosed placeholder \"{{{{{variable}\""
+            ),
+            TaskCatalogError::Generation { task, error } => {
+                write!(f, "Invalid tasks: task \"{task}\": {error}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TaskCatalogError {}
+
+/// Why the prompt of a document couldn't be built.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskError {
+    Unknown(String),
+    MissingVariable { task: String, variable: String },
+}
+
+impl fmt::Display for TaskError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskError::Unknown(task) => write!(f, "Task \"{task}\" is not defined"),
+            TaskError::MissingVariable { task, variable } => write!(
+                f,
+                "Prompt of task \"{task}\" uses variable \"{variable}\", which has no value"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TaskError {}
@@ -0,0 +1,129 @@
+use base64::{prelude::BASE64_STANDARD, Engine};
+use serde_json::json;
+use zero_shot_chat_with_document::{
+    chat::{Answer, DocumentBody, Question},
+    citation::CitationFormat,
+    document::S3Document,
+    source::{DocumentSource, InlineDocument},
+};
+
+#[test]
+fn reads_questions_in_camel_case() {
+    let question: Question = serde_json::from_value(json!({
+        "documentUri": "s3://documents/mrkl.pdf",
+        "question": "How does it handle arithmetic?",
+        "sessionId": "session-id",
+        "citationFormat": "markdown"
+    }))
+    .unwrap();
+
+    assert_eq!(
+        question.document_uri.as_deref(),
+        Some("s3://documents/mrkl.pdf")
+    );
+    assert_eq!(question.session_id.as_deref(), Some("session-id"));
+    assert_eq!(question.citation_format, Some(CitationFormat::Markdown));
+}
+
+#[test]
+fn writes_answers_in_camel_case() {
+    let answer = Answer {
+        answer: "A MRKL system is a modular architecture.".to_string(),
+        session_id: "session-id".to_string(),
+        citations: Vec::new(),
+        rendered: None,
+        guardrail_action: Some("INTERVENED".to_string()),
+    };
+
+    assert_eq!(
+        serde_json::to_value(&answer).unwrap(),
+        json!({
+            "answer": "A MRKL system is a modular architecture.",
+            "sessionId": "session-id",
+            "citations": [],
+            "guardrailAction": "INTERVENED"
+        })
+    );
+}
+
+fn question(document_uri: Option<&str>, document: Option<DocumentBody>) -> Question {
+    Question {
+        document_uri: document_uri.map(str::to_string),
+        document,
+        question: "What is a MRKL system?".to_string(),
+        session_id: None,
+        citation_format: None,
+    }
+}
+
+fn body(name: &str, data: &str) -> DocumentBody {
+    DocumentBody {
+        name: name.to_string(),
+        content_type: None,
+        data: data.to_string(),
+    }
+}
+
+#[test]
+fn reads_document_from_s3_uri() {
+    assert_eq!(
+        question(Some("s3://documents/mrkl.pdf"), None).source(),
+        Ok(DocumentSource::S3(S3Document {
+            bucket: "documents".to_string(),
+            key: "mrkl.pdf".to_string(),
+        }))
+    );
+    assert_eq!(
+        question(Some("https://example.com/mrkl.pdf"), None).source(),
+        Err(
+            r#"Invalid documentUri "https://example.com/mrkl.pdf", expected s3://<bucket>/<key>."#
+                .to_string()
+        )
+    );
+}
+
+#[test]
+fn reads_document_sent_inline() {
+    let source = question(
+        None,
+        Some(body("notes.md", &BASE64_STANDARD.encode("# Notes"))),
+    )
+    .source();
+
+    assert_eq!(
+        source,
+        Ok(DocumentSource::Inline(InlineDocument {
+            identifier: "notes.md".to_string(),
+            content_type: "text/markdown".to_string(),
+            data: b"# Notes".to_vec(),
+        }))
+    );
+}
+
+#[test]
+fn rejects_inline_documents_that_cant_be_sent() {
+    assert!(question(None, Some(body("notes.md", "not base64!")))
+        .source()
+        .unwrap_err()
+        .starts_with("Invalid base64 document data"));
+    assert_eq!(
+        question(None, Some(body("notes.md", ""))).source(),
+        Err("Invalid document: no content.".to_string())
+    );
+}
+
+#[test]
+fn expects_either_document_uri_or_document() {
+    assert_eq!(
+        question(
+            Some("s3://documents/mrkl.pdf"),
+            Some(body("notes.md", "IyBOb3Rlcw=="))
+        )
+        .source(),
+        Err("Expected either a documentUri or a document, not both.".to_string())
+    );
+    assert_eq!(
+        question(None, None).source(),
+        Err("Missing documentUri or document.".to_string())
+    );
+}
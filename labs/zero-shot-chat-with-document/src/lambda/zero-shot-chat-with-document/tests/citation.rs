@@ -0,0 +1,168 @@
+use zero_shot_chat_with_document::citation::{render, Citation, CitationFormat, Reference, Span};
+
+const TEXT: &str = "MRKL systems are modular. They use calculators.";
+
+fn reference(text: &str) -> Reference {
+    Reference {
+        text: text.to_string(),
+        location: Some("s3://documents/mrkl.pdf".to_string()),
+    }
+}
+
+fn spanned(start: usize, end: usize, references: &[&str]) -> Citation {
+    Citation {
+        text: None,
+        span: Some(Span { start, end }),
+        references: references.iter().map(|text| reference(text)).collect(),
+    }
+}
+
+#[test]
+fn ends_after_span() {
+    assert_eq!(spanned(0, 24, &["Modular."]).end(TEXT), Some(25));
+}
+
+#[test]
+fn ends_after_text_without_span() {
+    let citation = Citation {
+        text: Some("They use calculators.".to_string()),
+        span: None,
+        references: Vec::new(),
+    };
+
+    assert_eq!(citation.end(TEXT), Some(TEXT.chars().count()));
+    assert_eq!(citation.end("Another text."), None);
+    assert_eq!(
+        Citation {
+            text: Some(String::new()),
+            ..citation
+        }
+        .end(TEXT),
+        None
+    );
+}
+
+#[test]
+fn counts_characters_of_multibyte_text() {
+    let text = "Café: très modulaire. Il utilise des calculatrices.";
+    let citation = Citation {
+        text: Some("très modulaire.".to_string()),
+        span: None,
+        references: vec![reference("Modulaire.")],
+    };
+
+    assert_eq!(citation.end(text), Some(21));
+    assert_eq!(
+        render(text, &[citation], CitationFormat::Footnotes),
+        "Café: très modulaire.[1] Il utilise des calculatrices.\n\n\
+         [1] \"Modulaire.\" (s3://documents/mrkl.pdf)"
+    );
+}
+
+#[test]
+fn renders_footnotes_after_cited_parts() {
+    let citations = [
+        spanned(0, 24, &["MRKL is modular."]),
+        spanned(26, 46, &["Jurassic-X uses a calculator."]),
+    ];
+
+    assert_eq!(
+        render(TEXT, &citations, CitationFormat::Footnotes),
+        "MRKL systems are modular.[1] They use calculators.[2]\n\n\
+         [1] \"MRKL is modular.\" (s3://documents/mrkl.pdf)\n\
+         [2] \"Jurassic-X uses a calculator.\" (s3://documents/mrkl.pdf)"
+    );
+}
+
+#[test]
+fn renders_markdown_footnotes() {
+    let citations = [Citation {
+        text: Some("MRKL systems are modular.".to_string()),
+        span: None,
+        references: vec![Reference {
+            text: "MRKL is modular.".to_string(),
+            location: None,
+        }],
+    }];
+
+    assert_eq!(
+        render(TEXT, &citations, CitationFormat::Markdown),
+        "MRKL systems are modular.[^1] They use calculators.\n\n\
+         [^1]: > MRKL is modular."
+    );
+}
+
+#[test]
+fn keeps_number_order_of_markers_at_the_same_place() {
+    let citations = [
+        spanned(26, 46, &["Calculators."]),
+        spanned(0, 24, &["Modular.", "Neuro-symbolic."]),
+        spanned(26, 46, &["Arithmetic."]),
+    ];
+
+    let rendered = render(TEXT, &citations, CitationFormat::Footnotes);
+
+    assert!(
+        rendered.starts_with("MRKL systems are modular.[2][3] They use calculators.[1][4]\n\n"),
+        "{rendered}"
+    );
+}
+
+#[test]
+fn clamps_spans_past_the_end_of_the_text() {
+    let citations = [spanned(26, 500, &["Calculators."])];
+
+    assert_eq!(
+        render(TEXT, &citations, CitationFormat::Footnotes),
+        format!("{TEXT}[1]\n\n[1] \"Calculators.\" (s3://documents/mrkl.pdf)")
+    );
+}
+
+#[test]
+fn puts_markers_of_citations_not_found_at_the_end() {
+    let citations = [Citation {
+        text: Some("Not in the text.".to_string()),
+        span: None,
+        references: vec![reference("Somewhere.")],
+    }];
+
+    assert!(render(TEXT, &citations, CitationFormat::Footnotes).starts_with(&format!("{TEXT}[1]")));
+}
+
+#[test]
+fn returns_text_as_is_without_references() {
+    assert_eq!(render(TEXT, &[], CitationFormat::Footnotes), TEXT);
+    assert_eq!(
+        render(TEXT, &[spanned(0, 24, &[])], CitationFormat::Markdown),
+        TEXT
+    );
+}
+
+#[test]
+fn shortens_excerpts_to_a_single_line_of_200_characters() {
+    let passage = format!("{}\n\n{}", "a".repeat(150), "b".repeat(100));
+    let citations = [Citation {
+        text: None,
+        span: Some(Span { start: 0, end: 24 }),
+        references: vec![Reference {
+            text: passage,
+            location: None,
+        }],
+    }];
+
+    let rendered = render(TEXT, &citations, CitationFormat::Footnotes);
+
+    let expected = format!("[1] \"{} {}…\"", "a".repeat(150), "b".repeat(49));
+    assert!(rendered.ends_with(&expected), "{rendered}");
+
+    let exact = [Citation {
+        text: None,
+        span: Some(Span { start: 0, end: 24 }),
+        references: vec![Reference {
+            text: "c".repeat(200),
+            location: None,
+        }],
+    }];
+    assert!(render(TEXT, &exact, CitationFormat::Footnotes)
+        .ends_with(&format!("[1] \"{}\"", "c".repeat(200))));
+}
@@ -0,0 +1,283 @@
+use serde_json::json;
+use zero_shot_chat_with_document::{
+    compare::{
+        answers_document, check_documents, chunks, is_comparable, ChunkAnswer, Comparison,
+        ComparisonAnswer, DocumentAnswer, DocumentSet, Manifest,
+    },
+    document::S3Document,
+};
+
+#[test]
+fn reads_comparisons_in_camel_case() {
+    let comparison: Comparison = serde_json::from_value(json!({
+        "documentUris": ["s3://documents/a.pdf"],
+        "manifestUri": "s3://documents/manifest.json",
+        "question": "Which one is cheaper?"
+    }))
+    .unwrap();
+
+    assert_eq!(comparison.document_uris, ["s3://documents/a.pdf"]);
+    assert_eq!(
+        comparison.manifest_uri.as_deref(),
+        Some("s3://documents/manifest.json")
+    );
+}
+
+#[test]
+fn writes_comparison_answers_in_camel_case() {
+    let answer = ComparisonAnswer {
+        answer: "The first one.".to_string(),
+        documents: vec!["s3://documents/a.pdf".to_string()],
+        citations: Vec::new(),
+        document_answers: vec![DocumentAnswer {
+            document: "s3://documents/a.pdf".to_string(),
+            answer: "It costs 10.".to_string(),
+            citations: Vec::new(),
+        }],
+        chunk_answers: Vec::new(),
+    };
+
+    assert_eq!(
+        serde_json::to_value(&answer).unwrap(),
+        json!({
+            "answer": "The first one.",
+            "documents": ["s3://documents/a.pdf"],
+            "citations": [],
+            "documentAnswers": [{
+                "document": "s3://documents/a.pdf",
+                "answer": "It costs 10.",
+                "citations": []
+            }]
+        })
+    );
+
+    let answer = ComparisonAnswer {
+        chunk_answers: vec![ChunkAnswer {
+            documents: vec!["s3://documents/a.pdf".to_string()],
+            answer: "It is the cheapest of its chunk.".to_string(),
+            citations: Vec::new(),
+        }],
+        ..answer
+    };
+    assert_eq!(
+        serde_json::to_value(&answer).unwrap()["chunkAnswers"],
+        json!([{
+            "documents": ["s3://documents/a.pdf"],
+            "answer": "It is the cheapest of its chunk.",
+            "citations": []
+        }])
+    );
+}
+
+fn document(bucket: &str, key: &str) -> S3Document {
+    S3Document {
+        bucket: bucket.to_string(),
+        key: key.to_string(),
+    }
+}
+
+fn comparison(value: serde_json::Value) -> Comparison {
+    serde_json::from_value(value).unwrap()
+}
+
+#[test]
+fn lists_documents_of_uris() {
+    let comparison = comparison(json!({
+        "documentUris": ["s3://documents/a.pdf", "s3://reports/2024/b.pdf"],
+        "question": "Which one is cheaper?"
+    }));
+
+    assert_eq!(
+        comparison.document_set(),
+        Ok(DocumentSet::Listed(vec![
+            document("documents", "a.pdf"),
+            document("reports", "2024/b.pdf"),
+        ]))
+    );
+}
+
+#[test]
+fn rejects_invalid_document_uris() {
+    let comparison = comparison(json!({
+        "documentUris": ["s3://documents/a.pdf", "documents/b.pdf"],
+        "question": "Which one is cheaper?"
+    }));
+
+    assert_eq!(
+        comparison.document_set(),
+        Err(r#"Invalid document URI "documents/b.pdf", expected s3://<bucket>/<key>."#.to_string())
+    );
+}
+
+#[test]
+fn lists_documents_under_prefix() {
+    let set = |prefix: &str| {
+        comparison(json!({ "prefix": prefix, "question": "Which one is cheaper?" })).document_set()
+    };
+
+    assert_eq!(
+        set("s3://documents/reports/2024/"),
+        Ok(DocumentSet::Prefix {
+            bucket: "documents".to_string(),
+            prefix: "reports/2024/".to_string(),
+        })
+    );
+    assert_eq!(
+        set("s3://documents/"),
+        Ok(DocumentSet::Prefix {
+            bucket: "documents".to_string(),
+            prefix: String::new(),
+        })
+    );
+    for prefix in ["documents/reports/", "s3://documents", "s3:///reports/"] {
+        assert_eq!(
+            set(prefix),
+            Err(format!(
+                "Invalid prefix {prefix:?}, expected s3://<bucket>/<prefix>."
+            ))
+        );
+    }
+}
+
+#[test]
+fn lists_documents_of_manifest() {
+    let set = |uri: &str| {
+        comparison(json!({ "manifestUri": uri, "question": "Which one is cheaper?" }))
+            .document_set()
+    };
+
+    assert_eq!(
+        set("s3://documents/manifest.json"),
+        Ok(DocumentSet::Manifest(document(
+            "documents",
+            "manifest.json"
+        )))
+    );
+    assert_eq!(
+        set("manifest.json"),
+        Err(r#"Invalid manifestUri "manifest.json", expected s3://<bucket>/<key>."#.to_string())
+    );
+}
+
+#[test]
+fn expects_exactly_one_document_set() {
+    assert_eq!(
+        comparison(json!({ "question": "Which one is cheaper?" })).document_set(),
+        Err("Missing documentUris, prefix or manifestUri.".to_string())
+    );
+    assert_eq!(
+        comparison(json!({
+            "documentUris": ["s3://documents/a.pdf"],
+            "prefix": "s3://documents/",
+            "question": "Which one is cheaper?"
+        }))
+        .document_set(),
+        Err("Expected only one of documentUris, prefix or manifestUri.".to_string())
+    );
+    assert_eq!(
+        comparison(json!({
+            "prefix": "s3://documents/",
+            "manifestUri": "s3://documents/manifest.json",
+            "question": "Which one is cheaper?"
+        }))
+        .document_set(),
+        Err("Expected only one of documentUris, prefix or manifestUri.".to_string())
+    );
+}
+
+#[test]
+fn reads_manifests() {
+    assert_eq!(
+        Manifest::from_json(br#"{"documents": ["s3://documents/a.pdf", "s3://documents/b.pdf"]}"#),
+        Ok(vec![
+            document("documents", "a.pdf"),
+            document("documents", "b.pdf")
+        ])
+    );
+    assert!(Manifest::from_json(br#"["s3://documents/a.pdf"]"#)
+        .unwrap_err()
+        .starts_with("Invalid manifest: "));
+    assert_eq!(
+        Manifest::from_json(br#"{"documents": ["a.pdf"]}"#),
+        Err(r#"Invalid document URI "a.pdf", expected s3://<bucket>/<key>."#.to_string())
+    );
+}
+
+#[test]
+fn compares_documents_but_not_folders_or_summaries() {
+    assert!(is_comparable("reports/2024/q1.pdf"));
+    assert!(!is_comparable("reports/2024/"));
+    assert!(!is_comparable("reports/2024/q1.pdf.summary.json"));
+}
+
+#[test]
+fn removes_documents_listed_twice() {
+    let documents = vec![
+        document("documents", "b.pdf"),
+        document("documents", "a.pdf"),
+        document("documents", "b.pdf"),
+        document("reports", "b.pdf"),
+    ];
+
+    assert_eq!(
+        check_documents(documents),
+        Ok(vec![
+            document("documents", "b.pdf"),
+            document("documents", "a.pdf"),
+            document("reports", "b.pdf"),
+        ])
+    );
+}
+
+#[test]
+fn expects_at_least_two_documents() {
+    assert_eq!(
+        check_documents(Vec::new()),
+        Err("No documents to compare.".to_string())
+    );
+    assert_eq!(
+        check_documents(vec![
+            document("documents", "a.pdf"),
+            document("documents", "a.pdf")
+        ]),
+        Err("Only s3://documents/a.pdf to compare, expected at least 2 documents.".to_string())
+    );
+}
+
+#[test]
+fn splits_documents_in_even_chunks_of_at_most_max() {
+    let sizes = |count: usize, max: usize| {
+        chunks(&vec![0; count], max)
+            .iter()
+            .map(|chunk| chunk.len())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(sizes(2, 20), [2]);
+    assert_eq!(sizes(20, 20), [20]);
+    assert_eq!(sizes(21, 20), [11, 10]);
+    assert_eq!(sizes(41, 20), [14, 14, 13]);
+    assert_eq!(sizes(7, 3), [3, 2, 2]);
+    assert!(chunks::<u8>(&[], 3).is_empty());
+
+    let documents = [1, 2, 3, 4, 5];
+    assert_eq!(chunks(&documents, 3), [&[1, 2, 3][..], &[4, 5][..]]);
+}
+
+#[test]
+fn writes_answers_under_their_documents() {
+    let first = ["s3://documents/a.pdf".to_string()];
+    let chunk = [
+        "s3://documents/b.pdf".to_string(),
+        "s3://documents/c.pdf".to_string(),
+    ];
+
+    assert_eq!(
+        answers_document([
+            (&first[..], "It costs 10.\n"),
+            (&chunk[..], "Both cost 12."),
+        ]),
+        "## s3://documents/a.pdf\n\nIt costs 10.\n\n\
+        ## s3://documents/b.pdf, s3://documents/c.pdf\n\nBoth cost 12."
+    );
+}
@@ -0,0 +1,91 @@
+use aws_lambda_events::event::s3::S3EventRecord;
+use zero_shot_chat_with_document::document::{decode_key, RecordError, S3Document};
+
+fn record(bucket: Option<&str>, key: Option<&str>) -> S3EventRecord {
+    let mut record = S3EventRecord::default();
+    record.s3.bucket.name = bucket.map(str::to_string);
+    record.s3.object.key = key.map(str::to_string);
+    record
+}
+
+#[test]
+fn decodes_keys_of_event_notifications() {
+    assert_eq!(
+        decode_key("my+document%281%29.pdf").as_deref(),
+        Some("my document(1).pdf")
+    );
+    assert_eq!(
+        decode_key("reports/caf%C3%A9.pdf").as_deref(),
+        Some("reports/café.pdf")
+    );
+    assert_eq!(decode_key("a%2Bb.pdf").as_deref(), Some("a+b.pdf"));
+}
+
+#[test]
+fn rejects_keys_that_are_not_utf8() {
+    assert_eq!(decode_key("invalid%FF.pdf"), None);
+}
+
+#[test]
+fn reads_document_of_record() {
+    let record = record(Some("documents"), Some("reports/annual+report.pdf"));
+
+    assert_eq!(
+        S3Document::try_from(&record),
+        Ok(S3Document {
+            bucket: "documents".to_string(),
+            key: "reports/annual report.pdf".to_string(),
+        })
+    );
+}
+
+#[test]
+fn rejects_records_without_bucket() {
+    for bucket in [None, Some("")] {
+        assert_eq!(
+            S3Document::try_from(&record(bucket, Some("report.pdf"))),
+            Err(RecordError::MissingBucket)
+        );
+    }
+}
+
+#[test]
+fn rejects_records_without_key() {
+    for key in [None, Some("")] {
+        assert_eq!(
+            S3Document::try_from(&record(Some("documents"), key)),
+            Err(RecordError::MissingKey {
+                bucket: "documents".to_string()
+            })
+        );
+    }
+}
+
+#[test]
+fn rejects_records_with_invalid_keys() {
+    assert_eq!(
+        S3Document::try_from(&record(Some("documents"), Some("report%FF.pdf"))),
+        Err(RecordError::InvalidKey {
+            bucket: "documents".to_string(),
+            key: "report%FF.pdf".to_string(),
+        })
+    );
+}
+
+#[test]
+fn parses_s3_uris() {
+    let document = S3Document::from_uri("s3://documents/reports/2024.pdf").unwrap();
+
+    assert_eq!(document.bucket, "documents");
+    assert_eq!(document.key, "reports/2024.pdf");
+    assert_eq!(document.uri(), "s3://documents/reports/2024.pdf");
+
+    for uri in [
+        "documents/report.pdf",
+        "s3://documents",
+        "s3:///report.pdf",
+        "s3://documents/",
+    ] {
+        assert_eq!(S3Document::from_uri(uri), None, "{uri}");
+    }
+}
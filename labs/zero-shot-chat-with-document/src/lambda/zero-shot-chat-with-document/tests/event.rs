@@ -0,0 +1,132 @@
+use aws_lambda_events::event::s3::{S3Event, S3EventRecord};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use serde::Deserialize;
+use serde_json::json;
+use zero_shot_chat_with_document::event::{HttpRequest, HttpRequestContext, Request, Route};
+
+fn http(raw_path: &str, stage: &str) -> HttpRequest {
+    HttpRequest {
+        raw_path: raw_path.to_string(),
+        request_context: HttpRequestContext {
+            stage: Some(stage.to_string()),
+        },
+        ..HttpRequest::default()
+    }
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Body {
+    question: String,
+}
+
+#[test]
+fn dispatches_s3_notifications() {
+    let mut record = S3EventRecord::default();
+    record.s3.bucket.name = Some("documents".to_string());
+    record.s3.object.key = Some("report.pdf".to_string());
+    let event = serde_json::to_value(S3Event {
+        records: vec![record],
+    })
+    .unwrap();
+
+    let Request::Uploads(event) = serde_json::from_value(event).unwrap() else {
+        panic!("expected uploads");
+    };
+    assert_eq!(
+        event.records[0].s3.object.key.as_deref(),
+        Some("report.pdf")
+    );
+}
+
+#[test]
+fn dispatches_http_requests() {
+    let event = json!({
+        "version": "2.0",
+        "rawPath": "/compare",
+        "requestContext": { "stage": "$default", "http": { "method": "POST" } },
+        "body": "{\"question\": \"Which one is cheaper?\"}",
+        "isBase64Encoded": false
+    });
+
+    let Request::Http(request) = serde_json::from_value(event).unwrap() else {
+        panic!("expected an HTTP request");
+    };
+    assert_eq!(request.route(), Some(Route::Compare));
+    assert_eq!(
+        request.json(),
+        Ok(Body {
+            question: "Which one is cheaper?".to_string()
+        })
+    );
+}
+
+#[test]
+fn rejects_other_events() {
+    let error = serde_json::from_value::<Request>(json!({ "question": "What is a MRKL system?" }))
+        .unwrap_err();
+
+    assert!(error.to_string().contains("unsupported event"), "{error}");
+}
+
+#[test]
+fn routes_function_url_paths() {
+    assert_eq!(http("/", "$default").route(), Some(Route::Answer));
+    assert_eq!(http("", "$default").route(), Some(Route::Answer));
+    assert_eq!(http("/compare", "$default").route(), Some(Route::Compare));
+    assert_eq!(http("/compare/", "$default").route(), Some(Route::Compare));
+    assert_eq!(http("/ask", "$default").route(), None);
+    assert_eq!(http("/reports/compare", "$default").route(), None);
+    assert_eq!(HttpRequest::default().route(), Some(Route::Answer));
+}
+
+#[test]
+fn routes_api_gateway_paths_of_a_stage() {
+    assert_eq!(http("/prod", "prod").route(), Some(Route::Answer));
+    assert_eq!(http("/prod/", "prod").route(), Some(Route::Answer));
+    assert_eq!(http("/prod/compare", "prod").route(), Some(Route::Compare));
+    assert_eq!(http("/prod/ask", "prod").route(), None);
+    assert_eq!(http("/production/compare", "prod").route(), None);
+}
+
+#[test]
+fn parses_base64_bodies() {
+    let request = HttpRequest {
+        body: Some(BASE64_STANDARD.encode(r#"{"question": "What is a MRKL system?"}"#)),
+        is_base64_encoded: true,
+        ..http("/", "$default")
+    };
+
+    assert_eq!(
+        request.json(),
+        Ok(Body {
+            question: "What is a MRKL system?".to_string()
+        })
+    );
+}
+
+#[test]
+fn rejects_invalid_bodies() {
+    let base64 = HttpRequest {
+        body: Some("not base64!".to_string()),
+        is_base64_encoded: true,
+        ..http("/", "$default")
+    };
+    let json = HttpRequest {
+        body: Some("question=What".to_string()),
+        ..http("/", "$default")
+    };
+    let empty = http("/", "$default");
+
+    assert!(base64
+        .json::<Body>()
+        .unwrap_err()
+        .starts_with("Invalid base64 request body"));
+    assert!(json
+        .json::<Body>()
+        .unwrap_err()
+        .starts_with("Invalid request body"));
+    assert!(empty
+        .json::<Body>()
+        .unwrap_err()
+        .starts_with("Invalid request body"));
+}
@@ -0,0 +1,261 @@
+use aws_smithy_types::{Document, Number};
+use std::collections::HashMap;
+use zero_shot_chat_with_document::generation::{
+    GenerationError, GenerationSettings, GuardrailSettings, InferenceSettings,
+};
+
+fn settings(source: &str) -> Result<GenerationSettings, GenerationError> {
+    GenerationSettings::from_yaml(source)
+}
+
+#[test]
+fn reads_every_setting() {
+    let settings = settings(
+        r#"
+promptTemplate: "Only use these passages: $search_results$"
+inference:
+  temperature: 0.2
+  topP: 0.9
+  maxTokens: 1024
+  stopSequences: ["\n\nHuman:"]
+guardrail:
+  id: guardrail-id
+  version: "1"
+additionalModelRequestFields:
+  top_k: 50
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        settings.prompt_template.as_deref(),
+        Some("Only use these passages: $search_results$")
+    );
+    assert_eq!(
+        settings.inference,
+        Some(InferenceSettings {
+            temperature: Some(0.2),
+            top_p: Some(0.9),
+            max_tokens: Some(1024),
+            stop_sequences: Some(vec!["\n\nHuman:".to_string()]),
+        })
+    );
+    assert_eq!(
+        settings.guardrail,
+        Some(GuardrailSettings {
+            id: "guardrail-id".to_string(),
+            version: "1".to_string(),
+        })
+    );
+}
+
+#[test]
+fn rejects_prompt_templates_without_search_results() {
+    assert_eq!(
+        settings("promptTemplate: Summarize the passages."),
+        Err(GenerationError::MissingSearchResults)
+    );
+}
+
+#[test]
+fn rejects_temperature_and_top_p_out_of_range() {
+    assert_eq!(
+        settings("inference: { temperature: 1.5 }"),
+        Err(GenerationError::OutOfRange {
+            setting: "temperature",
+            value: 1.5,
+        })
+    );
+    assert_eq!(
+        settings("inference: { topP: -0.1 }"),
+        Err(GenerationError::OutOfRange {
+            setting: "topP",
+            value: -0.1,
+        })
+    );
+    assert!(settings("inference: { temperature: 0, topP: 1 }").is_ok());
+}
+
+#[test]
+fn rejects_max_tokens_that_arent_positive() {
+    assert_eq!(
+        settings("inference: { maxTokens: 0 }"),
+        Err(GenerationError::InvalidMaxTokens(0))
+    );
+    assert_eq!(
+        settings("inference: { maxTokens: -1 }"),
+        Err(GenerationError::InvalidMaxTokens(-1))
+    );
+}
+
+#[test]
+fn accepts_up_to_4_stop_sequences() {
+    assert!(settings(r#"inference: { stopSequences: ["a", "b", "c", "d"] }"#).is_ok());
+    assert_eq!(
+        settings(r#"inference: { stopSequences: ["a", "b", "c", "d", "e"] }"#),
+        Err(GenerationError::TooManyStopSequences(5))
+    );
+}
+
+#[test]
+fn accepts_guardrail_version_numbers_or_draft() {
+    let guardrail = |version: &str| {
+        settings(&format!(
+            "guardrail: {{ id: guardrail-id, version: {version:?} }}"
+        ))
+    };
+
+    assert!(guardrail("DRAFT").is_ok());
+    assert!(guardrail("1").is_ok());
+    for version in ["v1", "draft", "", "1.0"] {
+        assert_eq!(
+            guardrail(version),
+            Err(GenerationError::InvalidGuardrailVersion(
+                version.to_string()
+            ))
+        );
+    }
+    assert_eq!(
+        settings(r#"guardrail: { id: " ", version: "1" }"#),
+        Err(GenerationError::MissingGuardrailId)
+    );
+}
+
+#[test]
+fn takes_task_settings_over_configured_ones() {
+    let configured = settings(
+        r#"
+promptTemplate: "Configured: $search_results$"
+inference:
+  temperature: 0.7
+  maxTokens: 1024
+guardrail:
+  id: configured
+  version: "1"
+additionalModelRequestFields:
+  top_k: 50
+  top_a: 1
+"#,
+    )
+    .unwrap();
+    let task = settings(
+        r#"
+inference:
+  temperature: 0.1
+additionalModelRequestFields:
+  top_k: 10
+"#,
+    )
+    .unwrap();
+
+    let merged = configured.merged(Some(&task));
+
+    assert_eq!(
+        merged.prompt_template.as_deref(),
+        Some("Configured: $search_results$")
+    );
+    assert_eq!(
+        merged.inference,
+        Some(InferenceSettings {
+            temperature: Some(0.1),
+            max_tokens: Some(1024),
+            ..InferenceSettings::default()
+        })
+    );
+    assert_eq!(merged.guardrail, configured.guardrail);
+    assert_eq!(
+        merged.additional_model_request_fields,
+        Some(serde_json::from_str(r#"{"top_k": 10, "top_a": 1}"#).unwrap())
+    );
+
+    assert_eq!(configured.merged(None), configured);
+    assert_eq!(GenerationSettings::default().merged(Some(&task)), task);
+}
+
+#[test]
+fn uses_model_defaults_without_settings() {
+    assert_eq!(GenerationSettings::default().configuration().unwrap(), None);
+}
+
+#[test]
+fn configures_the_generation() {
+    let configuration = settings(
+        r#"
+promptTemplate: "Only use these passages: $search_results$"
+inference:
+  temperature: 0.2
+guardrail:
+  id: " guardrail-id "
+  version: DRAFT
+"#,
+    )
+    .unwrap()
+    .configuration()
+    .unwrap()
+    .expect("generation configuration");
+
+    assert_eq!(
+        configuration
+            .prompt_template()
+            .and_then(|template| template.text_prompt_template()),
+        Some("Only use these passages: $search_results$")
+    );
+    assert_eq!(
+        configuration
+            .inference_config()
+            .and_then(|config| config.text_inference_config())
+            .and_then(|config| config.temperature()),
+        Some(0.2)
+    );
+    let guardrail = configuration.guardrail_configuration().unwrap();
+    assert_eq!(guardrail.guardrail_id(), "guardrail-id");
+    assert_eq!(guardrail.guardrail_version(), "DRAFT");
+    assert_eq!(configuration.additional_model_request_fields(), None);
+}
+
+#[test]
+fn converts_additional_fields_to_documents() {
+    let configuration = settings(
+        r#"
+additionalModelRequestFields:
+  top_k: 50
+  offset: -1
+  penalty: 0.5
+  stream: false
+  stop: null
+  name: claude
+  tags: [a, 1]
+  nested: { depth: 2 }
+"#,
+    )
+    .unwrap()
+    .configuration()
+    .unwrap()
+    .expect("generation configuration");
+
+    assert_eq!(
+        configuration.additional_model_request_fields(),
+        Some(&HashMap::from([
+            ("top_k".to_string(), Document::Number(Number::PosInt(50))),
+            ("offset".to_string(), Document::Number(Number::NegInt(-1))),
+            ("penalty".to_string(), Document::Number(Number::Float(0.5))),
+            ("stream".to_string(), Document::Bool(false)),
+            ("stop".to_string(), Document::Null),
+            ("name".to_string(), Document::String("claude".to_string())),
+            (
+                "tags".to_string(),
+                Document::Array(vec![
+                    Document::String("a".to_string()),
+                    Document::Number(Number::PosInt(1)),
+                ])
+            ),
+            (
+                "nested".to_string(),
+                Document::Object(HashMap::from([(
+                    "depth".to_string(),
+                    Document::Number(Number::PosInt(2))
+                )]))
+            ),
+        ]))
+    );
+}
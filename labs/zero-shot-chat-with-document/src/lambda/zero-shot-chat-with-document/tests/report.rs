@@ -0,0 +1,102 @@
+use serde_json::json;
+use zero_shot_chat_with_document::{
+    document::S3Document,
+    report::{BatchReport, Outcome, RecordReport},
+    sink::SummaryLocation,
+};
+
+fn document(key: &str) -> Option<S3Document> {
+    Some(S3Document {
+        bucket: "documents".to_string(),
+        key: key.to_string(),
+    })
+}
+
+fn succeeded(index: usize, key: &str) -> RecordReport {
+    RecordReport {
+        index,
+        document: document(key),
+        outcome: Outcome::Succeeded {
+            task: "summarize".to_string(),
+            summary: "A summary.".to_string(),
+            stored: SummaryLocation::S3 {
+                uri: format!("s3://documents/{key}.summary.json"),
+            },
+        },
+    }
+}
+
+fn failed(index: usize, document: Option<S3Document>) -> RecordReport {
+    RecordReport {
+        index,
+        document,
+        outcome: Outcome::Failed {
+            error: "Malformed record: no bucket name".to_string(),
+        },
+    }
+}
+
+fn skipped(index: usize, key: &str) -> RecordReport {
+    RecordReport {
+        index,
+        document: document(key),
+        outcome: Outcome::Skipped {
+            reason: "Summaries aren't summarized".to_string(),
+        },
+    }
+}
+
+#[test]
+fn counts_outcomes_keeping_record_order() {
+    let records = vec![
+        skipped(0, "a.pdf.summary.json"),
+        succeeded(1, "b.pdf"),
+        failed(2, None),
+        succeeded(3, "c.pdf"),
+    ];
+
+    let report = BatchReport::new(records.clone());
+
+    assert_eq!(report.succeeded, 2);
+    assert_eq!(report.failed, 1);
+    assert_eq!(report.skipped, 1);
+    assert_eq!(report.records, records);
+}
+
+#[test]
+fn reports_empty_batches() {
+    let report = BatchReport::new(Vec::new());
+
+    assert_eq!((report.succeeded, report.failed, report.skipped), (0, 0, 0));
+    assert!(report.records.is_empty());
+}
+
+#[test]
+fn serializes_records_flattened() {
+    let report = BatchReport::new(vec![succeeded(0, "b.pdf"), failed(1, None)]);
+
+    assert_eq!(
+        serde_json::to_value(&report).unwrap(),
+        json!({
+            "succeeded": 1,
+            "failed": 1,
+            "skipped": 0,
+            "records": [
+                {
+                    "index": 0,
+                    "bucket": "documents",
+                    "key": "b.pdf",
+                    "status": "SUCCEEDED",
+                    "task": "summarize",
+                    "summary": "A summary.",
+                    "stored": { "type": "s3", "uri": "s3://documents/b.pdf.summary.json" }
+                },
+                {
+                    "index": 1,
+                    "status": "FAILED",
+                    "error": "Malformed record: no bucket name"
+                }
+            ]
+        })
+    );
+}
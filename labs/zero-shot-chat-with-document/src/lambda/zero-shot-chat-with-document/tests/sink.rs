@@ -0,0 +1,134 @@
+use aws_sdk_dynamodb::types::AttributeValue;
+use chrono::{TimeZone, Utc};
+use std::collections::HashMap;
+use zero_shot_chat_with_document::{
+    citation::{Citation, Reference, Span},
+    document::S3Document,
+    sink::dynamodb::to_item,
+    summary::Summary,
+};
+
+fn summary() -> Summary {
+    Summary {
+        document: S3Document {
+            bucket: "documents".to_string(),
+            key: "reports/mrkl.pdf".to_string(),
+        },
+        task: "summary".to_string(),
+        summary: "MRKL systems combine language models with reasoning modules.".to_string(),
+        citations: Vec::new(),
+        rendered: None,
+        guardrail_action: None,
+        session_id: "session-id".to_string(),
+        model_arn: "arn:aws:bedrock:us-east-1::foundation-model/model".to_string(),
+        prompt: "Summarize in few sentences the given document.".to_string(),
+        requested_at: Utc.with_ymd_and_hms(2025, 1, 12, 18, 3, 51).unwrap(),
+        generated_at: Utc.with_ymd_and_hms(2025, 1, 12, 18, 3, 58).unwrap(),
+    }
+}
+
+fn s(value: &str) -> AttributeValue {
+    AttributeValue::S(value.to_string())
+}
+
+#[test]
+fn stores_summary_by_document_uri() {
+    let item = to_item(&summary());
+
+    assert_eq!(
+        item,
+        HashMap::from([
+            ("document".to_string(), s("s3://documents/reports/mrkl.pdf")),
+            ("bucket".to_string(), s("documents")),
+            ("key".to_string(), s("reports/mrkl.pdf")),
+            ("task".to_string(), s("summary")),
+            (
+                "summary".to_string(),
+                s("MRKL systems combine language models with reasoning modules.")
+            ),
+            ("citations".to_string(), AttributeValue::L(Vec::new())),
+            ("session_id".to_string(), s("session-id")),
+            (
+                "model_arn".to_string(),
+                s("arn:aws:bedrock:us-east-1::foundation-model/model")
+            ),
+            (
+                "prompt".to_string(),
+                s("Summarize in few sentences the given document.")
+            ),
+            ("requested_at".to_string(), s("2025-01-12T18:03:51+00:00")),
+            ("generated_at".to_string(), s("2025-01-12T18:03:58+00:00")),
+        ])
+    );
+}
+
+#[test]
+fn stores_rendered_summary_and_guardrail_action_when_set() {
+    let mut summary = summary();
+    summary.rendered = Some("MRKL systems...[1]\n\n[1] \"...\"".to_string());
+    summary.guardrail_action = Some("INTERVENED".to_string());
+
+    let item = to_item(&summary);
+
+    assert_eq!(
+        item.get("rendered"),
+        Some(&s("MRKL systems...[1]\n\n[1] \"...\""))
+    );
+    assert_eq!(item.get("guardrail_action"), Some(&s("INTERVENED")));
+}
+
+#[test]
+fn stores_citations_with_their_spans() {
+    let mut summary = summary();
+    summary.citations = vec![
+        Citation {
+            text: Some("MRKL systems combine language models".to_string()),
+            span: Some(Span { start: 0, end: 35 }),
+            references: vec![Reference {
+                text: "MRKL is a modular neuro-symbolic architecture.".to_string(),
+                location: Some("s3://documents/reports/mrkl.pdf".to_string()),
+            }],
+        },
+        Citation {
+            text: None,
+            span: None,
+            references: vec![Reference {
+                text: "Jurassic-X handles arithmetic.".to_string(),
+                location: None,
+            }],
+        },
+    ];
+
+    let item = to_item(&summary);
+
+    assert_eq!(
+        item.get("citations"),
+        Some(&AttributeValue::L(vec![
+            AttributeValue::M(HashMap::from([
+                (
+                    "text".to_string(),
+                    s("MRKL systems combine language models")
+                ),
+                ("start".to_string(), AttributeValue::N("0".to_string())),
+                ("end".to_string(), AttributeValue::N("35".to_string())),
+                (
+                    "references".to_string(),
+                    AttributeValue::L(vec![AttributeValue::M(HashMap::from([
+                        (
+                            "text".to_string(),
+                            s("MRKL is a modular neuro-symbolic architecture.")
+                        ),
+                        ("location".to_string(), s("s3://documents/reports/mrkl.pdf")),
+                    ]))])
+                ),
+            ])),
+            AttributeValue::M(HashMap::from([(
+                "references".to_string(),
+                AttributeValue::L(vec![AttributeValue::M(HashMap::from([(
+                    "text".to_string(),
+                    s("Jurassic-X handles arithmetic.")
+                )]))])
+            )])),
+        ]))
+    );
+}
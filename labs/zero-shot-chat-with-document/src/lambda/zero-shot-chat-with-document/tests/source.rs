@@ -0,0 +1,87 @@
+use zero_shot_chat_with_document::source::{
+    content_type_of, InlineDocument, InlineDocumentError, MAX_INLINE_DOCUMENT_SIZE,
+};
+
+#[test]
+fn accepts_supported_documents() {
+    let document = InlineDocument::new(" notes.md ", Some("text/markdown"), b"# Notes".to_vec());
+
+    assert_eq!(
+        document,
+        Ok(InlineDocument {
+            identifier: "notes.md".to_string(),
+            content_type: "text/markdown".to_string(),
+            data: b"# Notes".to_vec(),
+        })
+    );
+}
+
+#[test]
+fn drops_parameters_of_content_type() {
+    let document = InlineDocument::new(
+        "notes",
+        Some("Text/Plain; charset=utf-8"),
+        b"Notes".to_vec(),
+    )
+    .unwrap();
+
+    assert_eq!(document.content_type, "text/plain");
+}
+
+#[test]
+fn rejects_unsupported_content_type() {
+    assert_eq!(
+        InlineDocument::new("photo.pdf", Some("image/png"), b"PNG".to_vec()),
+        Err(InlineDocumentError::UnsupportedContentType(
+            "image/png".to_string()
+        ))
+    );
+}
+
+#[test]
+fn guesses_content_type_from_extension() {
+    let document = InlineDocument::new("REPORT.PDF", None, b"%PDF-1.7".to_vec()).unwrap();
+
+    assert_eq!(document.content_type, "application/pdf");
+    assert_eq!(
+        content_type_of("sales.2024.xlsx"),
+        Some("application/vnd.openxmlformats-officedocument.spreadsheetml.sheet")
+    );
+}
+
+#[test]
+fn rejects_unknown_extensions_without_content_type() {
+    for name in ["photo.png", "README"] {
+        assert_eq!(
+            InlineDocument::new(name, None, b"data".to_vec()),
+            Err(InlineDocumentError::UnknownContentType(name.to_string()))
+        );
+    }
+}
+
+#[test]
+fn rejects_documents_without_name() {
+    assert_eq!(
+        InlineDocument::new(" ", Some("text/plain"), b"Notes".to_vec()),
+        Err(InlineDocumentError::MissingIdentifier)
+    );
+}
+
+#[test]
+fn rejects_empty_documents() {
+    assert_eq!(
+        InlineDocument::new("notes.txt", None, Vec::new()),
+        Err(InlineDocumentError::Empty)
+    );
+}
+
+#[test]
+fn accepts_documents_up_to_10_mib() {
+    let document = InlineDocument::new("notes.txt", None, vec![b'a'; MAX_INLINE_DOCUMENT_SIZE]);
+    assert_eq!(document.unwrap().data.len(), 10 * 1024 * 1024);
+
+    assert_eq!(
+        InlineDocument::new("notes.txt", None, vec![b'a'; MAX_INLINE_DOCUMENT_SIZE + 1]),
+        Err(InlineDocumentError::TooLarge(MAX_INLINE_DOCUMENT_SIZE + 1))
+    );
+}
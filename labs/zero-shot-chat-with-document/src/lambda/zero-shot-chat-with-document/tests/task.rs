@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+use zero_shot_chat_with_document::{
+    document::S3Document,
+    task::{
+        render, ObjectAttributes, TaskCatalog, TaskCatalogError, TaskError, TaskPrompt,
+        DEFAULT_TASKS,
+    },
+};
+
+const TASKS: &str = r#"
+defaultTask: summary
+tasks:
+  summary:
+    prompt: Summarize the given document.
+  classification:
+    prompt: >-
+      Classify "{{fileName}}" of {{bucket}} into one of these categories: {{categories}}.
+    variables:
+      categories: invoice, contract
+  key-points:
+    prompt: Extract the key points of {{key}}.
+  translation:
+    prompt: Translate the given document to {{language}}.
+prefixes:
+  classify/: classification
+  classify/contracts/: key-points
+"#;
+
+fn catalog() -> TaskCatalog {
+    TaskCatalog::from_yaml(TASKS).expect("valid tasks")
+}
+
+fn attributes(metadata: &[(&str, &str)], tags: &[(&str, &str)]) -> ObjectAttributes {
+    let map = |pairs: &[(&str, &str)]| {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect::<HashMap<_, _>>()
+    };
+
+    ObjectAttributes {
+        metadata: map(metadata),
+        tags: map(tags),
+    }
+}
+
+fn document(key: &str) -> S3Document {
+    S3Document {
+        bucket: "documents".to_string(),
+        key: key.to_string(),
+    }
+}
+
+#[test]
+fn selects_task_of_metadata_over_tag_prefix_and_default() {
+    let catalog = catalog();
+    let key = "classify/contracts/lease.pdf";
+
+    assert_eq!(
+        catalog.select(
+            key,
+            &attributes(&[("task", "translation")], &[("task", "summary")])
+        ),
+        "translation"
+    );
+    assert_eq!(
+        catalog.select(key, &attributes(&[], &[("task", " summary ")])),
+        "summary"
+    );
+    assert_eq!(
+        catalog.select(key, &ObjectAttributes::default()),
+        "key-points"
+    );
+    assert_eq!(
+        catalog.select("classify/invoice.pdf", &ObjectAttributes::default()),
+        "classification"
+    );
+    assert_eq!(
+        catalog.select("reports/2024.pdf", &ObjectAttributes::default()),
+        "summary"
+    );
+}
+
+#[test]
+fn renders_prompt_with_document_variables() {
+    let prompt = catalog()
+        .prompt(
+            &document("classify/invoice.pdf"),
+            &ObjectAttributes::default(),
+        )
+        .unwrap();
+
+    assert_eq!(
+        prompt,
+        TaskPrompt {
+            task: "classification".to_string(),
+            prompt: r#"Classify "invoice.pdf" of documents into one of these categories: invoice, contract."#
+                .to_string(),
+            generation: None,
+        }
+    );
+}
+
+#[test]
+fn takes_variables_of_metadata_over_tags_over_task() {
+    let catalog = catalog();
+    let document = document("classify/invoice.pdf");
+
+    let prompt = |attributes| catalog.prompt(&document, &attributes).unwrap().prompt;
+
+    assert!(prompt(attributes(&[], &[("categories", "receipt")])).ends_with("categories: receipt."));
+    assert!(prompt(attributes(
+        &[("categories", "resume")],
+        &[("categories", "receipt")]
+    ))
+    .ends_with("categories: resume."));
+}
+
+#[test]
+fn fails_on_variables_without_value() {
+    let error = catalog()
+        .prompt(
+            &document("notes.pdf"),
+            &attributes(&[("task", "translation")], &[]),
+        )
+        .unwrap_err();
+
+    assert_eq!(
+        error,
+        TaskError::MissingVariable {
+            task: "translation".to_string(),
+            variable: "language".to_string(),
+        }
+    );
+}
+
+#[test]
+fn fails_on_unknown_task_of_document() {
+    let error = catalog()
+        .prompt(
+            &document("notes.pdf"),
+            &attributes(&[("task", "poem")], &[]),
+        )
+        .unwrap_err();
+
+    assert_eq!(error, TaskError::Unknown("poem".to_string()));
+}
+
+#[test]
+fn renders_templates() {
+    let value = |variable: &str| (variable == "name").then_some("Pedro");
+
+    assert_eq!(
+        render("Hi {{ name }}, {{name}}!", value),
+        Ok("Hi Pedro, Pedro!".to_string())
+    );
+    assert_eq!(
+        render("No variables", value),
+        Ok("No variables".to_string())
+    );
+    assert_eq!(render("Hi {{flavor}}", value), Err("flavor".to_string()));
+    assert_eq!(render("Hi {{name", value), Err("name".to_string()));
+}
+
+#[test]
+fn rejects_unclosed_placeholders() {
+    let error = TaskCatalog::from_yaml(
+        r#"
+defaultTask: summary
+tasks:
+  summary:
+    prompt: Summarize {{fileName for me.
+"#,
+    )
+    .unwrap_err();
+
+    assert_eq!(
+        error,
+        TaskCatalogError::UnclosedPlaceholder {
+            task: "summary".to_string(),
+            variable: "fileName for me.".to_string(),
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        r#"Invalid tasks: prompt of task "summary" has an unclosed placeholder "{{fileName for me.""#
+    );
+}
+
+#[test]
+fn rejects_unknown_default_task() {
+    let error = TaskCatalog::from_yaml(
+        r#"
+defaultTask: translation
+tasks:
+  summary:
+    prompt: Summarize the given document.
+"#,
+    )
+    .unwrap_err();
+
+    assert_eq!(
+        error,
+        TaskCatalogError::UnknownTask("translation".to_string())
+    );
+}
+
+#[test]
+fn rejects_unknown_task_of_prefix() {
+    let error = TaskCatalog::from_yaml(
+        r#"
+defaultTask: summary
+tasks:
+  summary:
+    prompt: Summarize the given document.
+prefixes:
+  translate/: translation
+"#,
+    )
+    .unwrap_err();
+
+    assert_eq!(
+        error,
+        TaskCatalogError::UnknownTask("translation".to_string())
+    );
+}
+
+#[test]
+fn rejects_empty_prompts() {
+    let error = TaskCatalog::from_yaml(
+        r#"{"defaultTask": "summary", "tasks": {"summary": {"prompt": " "}}}"#,
+    )
+    .unwrap_err();
+
+    assert_eq!(error, TaskCatalogError::EmptyPrompt("summary".to_string()));
+}
+
+#[test]
+fn loads_default_tasks() {
+    let catalog = TaskCatalog::from_yaml(DEFAULT_TASKS).expect("valid default tasks");
+
+    let prompt = catalog
+        .prompt(&document("mrkl.pdf"), &ObjectAttributes::default())
+        .unwrap();
+    assert_eq!(prompt.task, "summary");
+    assert_eq!(
+        prompt.prompt,
+        "Summarize in few sentences the given document."
+    );
+
+    for task in ["key-points", "questions", "classification"] {
+        let prompt = catalog
+            .prompt(&document("mrkl.pdf"), &attributes(&[("task", task)], &[]))
+            .unwrap();
+        assert_eq!(prompt.task, task);
+    }
+}
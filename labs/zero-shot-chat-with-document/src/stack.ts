@@ -1,14 +1,17 @@
 import { RustLambdaFunctionBuilder } from '@luisk-genai-labs/shared-constructs/src/lambda/rust-lambda-function-builder';
 import * as cdk from 'aws-cdk-lib';
 import * as bedrock from 'aws-cdk-lib/aws-bedrock';
+import * as dynamodb from 'aws-cdk-lib/aws-dynamodb';
 import * as iam from 'aws-cdk-lib/aws-iam';
-import { IFunction } from 'aws-cdk-lib/aws-lambda';
+import { FunctionUrlAuthType, IFunction } from 'aws-cdk-lib/aws-lambda';
 import { S3EventSourceV2 } from 'aws-cdk-lib/aws-lambda-event-sources';
 import * as s3 from 'aws-cdk-lib/aws-s3';
 import { join } from 'node:path';
 
 export class ZeroShotChatWithDocumentStack extends cdk.Stack {
     protected _bucket: s3.Bucket;
+    protected _summaryBucket?: s3.Bucket;
+    protected _summaryTable?: dynamodb.Table;
     protected _lambda: IFunction;
     
     constructor(scope: cdk.App, id: string, props: cdk.StackProps) {
@@ -21,7 +24,7 @@ export class ZeroShotChatWithDocumentStack extends cdk.Stack {
             bucketName: `zero-shot-chat-with-document-${id.toLowerCase()}`,
         });
         
-        this._lambda = new RustLambdaFunctionBuilder(this, `Lambda${id}`, {
+        const lambdaBuilder = new RustLambdaFunctionBuilder(this, `Lambda${id}`, {
             name: 'zero-shot-chat-with-document',
             path: join(__dirname, 'lambda', 'zero-shot-chat-with-document'),
         })
@@ -29,7 +32,30 @@ export class ZeroShotChatWithDocumentStack extends cdk.Stack {
         .withDuration(30)
         .withBucket(this._bucket, 'BUCKET_NAME')
         .withEnvironmentVariable('MODEL_ARN', foundationalModel.modelArn)
-        .withEnvironmentVariable('AWS_LAMBDA_DISABLE_CLOUDWATCH_LOGS_DATA_PROTECTION', 'true')
+        .withEnvironmentVariable('AWS_LAMBDA_DISABLE_CLOUDWATCH_LOGS_DATA_PROTECTION', 'true');
+
+        // Summaries are stored in a DynamoDB table when deployed with SUMMARY_SINK=dynamodb, otherwise in a bucket of
+        // their own, as summaries written to the documents bucket would trigger the lambda function again
+        if (process.env.SUMMARY_SINK === 'dynamodb') {
+            this._summaryTable = new dynamodb.Table(this, `SummaryTable${id}`, {
+                partitionKey: {
+                    name: 'document',
+                    type: dynamodb.AttributeType.STRING,
+                },
+            });
+
+            lambdaBuilder
+                .withEnvironmentVariable('SUMMARY_SINK', 'dynamodb')
+                .withDynamoDBTable(this._summaryTable, 'SUMMARY_TABLE');
+        } else {
+            this._summaryBucket = new s3.Bucket(this, `SummaryBucket${id}`, {
+                bucketName: `zero-shot-chat-with-document-summaries-${id.toLowerCase()}`,
+            });
+
+            lambdaBuilder.withBucket(this._summaryBucket, 'SUMMARY_BUCKET');
+        }
+
+        this._lambda = lambdaBuilder
         .attachInlinePolicy(
             new iam.Policy(this, `BedrockPolicy${id}`, {
                 statements: [
@@ -51,6 +77,16 @@ export class ZeroShotChatWithDocumentStack extends cdk.Stack {
                             '*',
                         ],
                     }),
+                    // Guardrails set in the generation settings
+                    new iam.PolicyStatement({
+                        effect: iam.Effect.ALLOW,
+                        actions: [
+                            'bedrock:ApplyGuardrail',
+                        ],
+                        resources: [
+                            `arn:aws:bedrock:${this.region}:${this.account}:guardrail/*`,
+                        ],
+                    }),
                 ],
             })
         )
@@ -64,5 +100,14 @@ export class ZeroShotChatWithDocumentStack extends cdk.Stack {
                 ],
             })
         );
+
+        // Questions about the uploaded documents, requests must be signed with SigV4
+        const functionUrl = this._lambda.addFunctionUrl({
+            authType: FunctionUrlAuthType.AWS_IAM,
+        });
+
+        new cdk.CfnOutput(this, `FunctionUrl${id}`, {
+            value: functionUrl.url,
+        });
     }
 }